@@ -237,6 +237,7 @@ impl Global {
 
 #[unstable(feature = "allocator_api", issue = "32838")]
 #[cfg(not(test))]
+#[safety::contracts(from = core::ub_checks::contracts::Allocator)]
 unsafe impl Allocator for Global {
     #[inline]
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
@@ -450,3 +451,69 @@ impl<T: Copy> WriteCloneIntoRaw for T {
         unsafe { target.copy_from_nonoverlapping(self, 1) };
     }
 }
+
+#[cfg(any(all(kani, not(test)), safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+mod verify {
+    use super::*;
+    use core::kani;
+    use core::ub_checks::Invariant;
+
+    /// The allocator whose contracts are checked. In the unit tests, `Global` is the one of `std`,
+    /// which inherits no contract, so the instrumented shadow allocator is checked instead.
+    #[cfg(not(test))]
+    type Checked = Global;
+    #[cfg(test)]
+    type Checked = crate::testing::shadow_alloc::ShadowAlloc;
+
+    #[cfg(not(test))]
+    fn allocator() -> &'static Checked {
+        &Global
+    }
+    #[cfg(test)]
+    fn allocator() -> &'static Checked {
+        crate::testing::shadow_alloc::shadow()
+    }
+
+    #[kani::proof_for_contract(<Checked as Allocator>::allocate)]
+    pub fn check_allocate() {
+        let layout: Layout = kani::any();
+        kani::assume(layout.is_safe());
+        if let Ok(block) = allocator().allocate(layout) {
+            // SAFETY: the block was just allocated with `layout`.
+            unsafe { allocator().deallocate(block.cast(), layout) };
+        }
+    }
+
+    #[kani::proof_for_contract(<Checked as Allocator>::grow)]
+    pub fn check_grow() {
+        let old_layout: Layout = kani::any();
+        kani::assume(old_layout.is_safe());
+        let new_size: usize = kani::any();
+        kani::assume(new_size >= old_layout.size());
+        let Ok(new_layout) = Layout::from_size_align(new_size, old_layout.align()) else { return };
+        let Ok(block) = allocator().allocate(old_layout) else { return };
+        // SAFETY: the block was just allocated with `old_layout`, and `new_layout` is at least as
+        // large.
+        if let Ok(block) = unsafe { allocator().grow(block.cast(), old_layout, new_layout) } {
+            // SAFETY: the block was just grown to `new_layout`.
+            unsafe { allocator().deallocate(block.cast(), new_layout) };
+        }
+    }
+
+    #[kani::proof_for_contract(<Checked as Allocator>::shrink)]
+    pub fn check_shrink() {
+        let old_layout: Layout = kani::any();
+        kani::assume(old_layout.is_safe());
+        let new_size: usize = kani::any();
+        kani::assume(new_size <= old_layout.size());
+        let Ok(new_layout) = Layout::from_size_align(new_size, old_layout.align()) else { return };
+        let Ok(block) = allocator().allocate(old_layout) else { return };
+        // SAFETY: the block was just allocated with `old_layout`, and `new_layout` is at most as
+        // large.
+        if let Ok(block) = unsafe { allocator().shrink(block.cast(), old_layout, new_layout) } {
+            // SAFETY: the block was just shrunk to `new_layout`.
+            unsafe { allocator().deallocate(block.cast(), new_layout) };
+        }
+    }
+}
@@ -21,6 +21,87 @@ fn allocate_zeroed() {
     }
 }
 
+#[test]
+fn shadow_alloc_tracks_allocations() {
+    use crate::testing::shadow_alloc::shadow;
+    use core::ub_checks::{can_read_slice, is_initialized, same_allocation};
+
+    let layout = Layout::new::<[u32; 4]>();
+    let ptr = shadow().allocate(layout).unwrap().cast::<u32>();
+    let other = shadow().allocate(layout).unwrap().cast::<u32>();
+    unsafe {
+        let p = ptr.as_ptr();
+        assert!(can_read_slice(p, 4));
+        assert!(!can_read_slice(p, 5));
+        assert!(!can_read_slice(p.wrapping_sub(1), 1));
+        assert!(same_allocation(p, p.add(4)));
+        assert!(!same_allocation(p, other.as_ptr()));
+
+        assert!(!is_initialized(p, 1));
+        p.write(7);
+        assert!(is_initialized(p, 1));
+        assert!(!is_initialized(p, 2));
+
+        let new_layout = Layout::new::<[u32; 8]>();
+        let grown = shadow().grow(ptr.cast(), layout, new_layout).unwrap().cast::<u32>();
+        assert!(!can_read_slice(p, 1));
+        assert!(is_initialized(grown.as_ptr(), 1));
+        assert!(!is_initialized(grown.as_ptr().add(4), 1));
+
+        shadow().deallocate(grown.cast(), new_layout);
+        shadow().deallocate(other.cast(), layout);
+    }
+}
+
+#[test]
+fn shadow_alloc_grow_keeps_unwritten_bytes_uninitialized() {
+    use crate::testing::shadow_alloc::shadow;
+    use core::ub_checks::is_initialized;
+
+    let layout = Layout::new::<[u32; 4]>();
+    let ptr = shadow().allocate(layout).unwrap().cast::<u32>();
+    unsafe {
+        ptr.as_ptr().write(7);
+        let new_layout = Layout::new::<[u32; 8]>();
+        let grown = shadow().grow(ptr.cast(), layout, new_layout).unwrap().cast::<u32>();
+        assert!(is_initialized(grown.as_ptr(), 1));
+        // The copied bytes that were never written are still uninitialized.
+        assert!(!is_initialized(grown.as_ptr().add(1), 1));
+        assert_eq!(grown.as_ptr().read(), 7);
+
+        let zeroed_layout = Layout::new::<[u32; 16]>();
+        let zeroed =
+            shadow().grow_zeroed(grown.cast(), new_layout, zeroed_layout).unwrap().cast::<u32>();
+        assert!(is_initialized(zeroed.as_ptr(), 1));
+        assert!(!is_initialized(zeroed.as_ptr().add(1), 1));
+        assert!(is_initialized(zeroed.as_ptr().add(8), 8));
+
+        shadow().deallocate(zeroed.cast(), zeroed_layout);
+    }
+}
+
+#[test]
+#[should_panic(expected = "double free")]
+fn shadow_alloc_double_free() {
+    use crate::testing::shadow_alloc::shadow;
+
+    let layout = Layout::new::<u64>();
+    let ptr = shadow().allocate(layout).unwrap().cast::<u8>();
+    unsafe {
+        shadow().deallocate(ptr, layout);
+        shadow().deallocate(ptr, layout);
+    }
+}
+
+#[test]
+#[should_panic(expected = "layout mismatch")]
+fn shadow_alloc_layout_mismatch() {
+    use crate::testing::shadow_alloc::shadow;
+
+    let ptr = shadow().allocate(Layout::new::<u64>()).unwrap().cast::<u8>();
+    unsafe { shadow().deallocate(ptr, Layout::new::<u32>()) }
+}
+
 #[bench]
 fn alloc_owned_small(b: &mut Bencher) {
     b.iter(|| {
@@ -3407,5 +3407,50 @@ impl fmt::Display for UnorderedKeyError {
 #[unstable(feature = "btree_cursors", issue = "107540")]
 impl Error for UnorderedKeyError {}
 
+/// Creates a map by up to `MAX` insertions and removals of arbitrary keys, so that it has an
+/// arbitrary length and its tree an arbitrary shape, including the underfull nodes that removals
+/// leave after rebalancing.
+#[cfg(any(kani, safety_random))]
+pub(crate) fn any_btree_map<K, V, const MAX: usize>() -> BTreeMap<K, V>
+where
+    K: Ord + core::kani::Arbitrary,
+    V: core::kani::Arbitrary,
+{
+    let mut map = BTreeMap::new();
+    let ops = core::kani::any_where(|ops: &usize| *ops <= MAX);
+    for i in 0..MAX {
+        if i < ops {
+            if core::kani::any() {
+                map.insert(core::kani::any(), core::kani::any());
+            } else {
+                map.remove(&core::kani::any::<K>());
+            }
+        }
+    }
+    map
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature="kani", issue="none")]
+mod verify {
+    use super::*;
+    use core::kani;
+
+    // Twelve insertions split the root, so that the removal can rebalance its children.
+    #[kani::proof]
+    #[kani::unwind(17)]
+    pub fn check_remove_rebalance() {
+        let mut map = any_btree_map::<u8, u8, 16>();
+        let key = kani::any::<u8>();
+        let len = map.len();
+        let removed = map.remove(&key).is_some();
+        assert_eq!(map.len(), len - removed as usize);
+        assert!(!map.contains_key(&key));
+        let keys: Vec<u8> = map.keys().copied().collect();
+        assert_eq!(keys.len(), map.len());
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -2513,3 +2513,18 @@ fn cursor_peek_prev_agrees_with_cursor_mut() {
     let prev = cursor.peek_prev();
     assert_matches!(prev, Some((&3, _)));
 }
+
+#[test]
+fn test_shadow_alloc() {
+    use crate::testing::shadow_alloc::shadow;
+
+    let mut map = BTreeMap::new_in(shadow());
+    for i in 0..MIN_INSERTS_HEIGHT_2 {
+        map.insert(i, i.to_string());
+    }
+    for i in (0..MIN_INSERTS_HEIGHT_2).step_by(2) {
+        map.remove(&i);
+    }
+    map.check();
+    assert_eq!(map.len(), MIN_INSERTS_HEIGHT_2 / 2);
+}
@@ -2964,3 +2964,39 @@ impl<T, const N: usize> From<[T; N]> for VecDeque<T> {
         deq
     }
 }
+
+/// Creates a deque of at most `MAX` arbitrary elements, whose length, capacity and head are
+/// arbitrary too, so that its elements may wrap around the end of the buffer.
+#[cfg(any(kani, safety_random))]
+pub(crate) fn any_vec_deque<T: core::kani::Arbitrary, const MAX: usize>() -> VecDeque<T> {
+    let capacity = core::kani::any_where(|capacity: &usize| *capacity <= MAX);
+    let mut deque = VecDeque::with_capacity(capacity);
+    // `head < capacity`, unless the capacity is zero.
+    let head = core::kani::any_where(|head: &usize| *head == 0 || *head < deque.capacity());
+    deque.head = head;
+    let len = core::kani::any_where(|len: &usize| *len <= capacity);
+    for i in 0..MAX {
+        if i < len {
+            // SAFETY: `i < capacity`, so the slot is in the buffer and is not initialized yet.
+            unsafe { deque.buffer_write(deque.to_physical_idx(i), core::kani::any()) };
+        }
+    }
+    deque.len = len;
+    deque
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature="kani", issue="none")]
+mod verify {
+    use super::*;
+    use core::kani;
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    pub fn check_make_contiguous() {
+        let mut deque = any_vec_deque::<u8, 4>();
+        let elements: Vec<u8> = deque.iter().copied().collect();
+        assert_eq!(deque.make_contiguous(), &elements[..]);
+        assert!(deque.as_slices().1.is_empty());
+    }
+}
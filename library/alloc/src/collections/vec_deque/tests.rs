@@ -1172,3 +1172,20 @@ fn issue_80303() {
     assert_eq!(vda, vdb);
     assert_eq!(hash_code(vda), hash_code(vdb));
 }
+
+#[test]
+fn test_shadow_alloc() {
+    use crate::testing::shadow_alloc::shadow;
+
+    let mut deq = VecDeque::new_in(shadow());
+    for i in 0..100 {
+        deq.push_front(i);
+        deq.push_back(i);
+    }
+    deq.rotate_left(17);
+    deq.make_contiguous();
+    deq.truncate(150);
+    deq.shrink_to_fit();
+    assert_eq!(deq.len(), 150);
+    assert_eq!(deq.iter().filter(|&&i| i == 0).count(), 2);
+}
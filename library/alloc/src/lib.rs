@@ -158,6 +158,7 @@
 #![feature(try_trait_v2)]
 #![feature(try_with_capacity)]
 #![feature(tuple_trait)]
+#![feature(ub_checks)]
 #![feature(unicode_internals)]
 #![feature(unsize)]
 #![feature(unwrap_infallible)]
@@ -170,6 +171,7 @@
 #![cfg_attr(not(test), feature(coroutine_trait))]
 #![cfg_attr(test, feature(panic_update_hook))]
 #![cfg_attr(test, feature(test))]
+#![cfg_attr(any(kani, safety_random), feature(kani))]
 #![feature(allocator_internals)]
 #![feature(allow_internal_unstable)]
 #![feature(c_unwind)]
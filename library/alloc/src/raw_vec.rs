@@ -66,6 +66,7 @@ impl Cap {
 /// `usize::MAX`. This means that you need to be careful when round-tripping this type with a
 /// `Box<[T]>`, since `capacity()` won't yield the length.
 #[allow(missing_debug_implementations)]
+#[safety::invariant(T::IS_ZST || self.cap.0 <= isize::MAX as usize / mem::size_of::<T>())]
 pub(crate) struct RawVec<T, A: Allocator = Global> {
     ptr: Unique<T>,
     /// Never used for ZSTs; it's `capacity()`'s responsibility to return usize::MAX in that case.
@@ -254,6 +255,7 @@ impl<T, A: Allocator> RawVec<T, A> {
     /// If the `ptr` and `capacity` come from a `RawVec` created via `alloc`, then this is
     /// guaranteed.
     #[inline]
+    #[safety::invariant]
     pub unsafe fn from_raw_parts_in(ptr: *mut T, capacity: usize, alloc: A) -> Self {
         let cap = if T::IS_ZST { Cap::ZERO } else { unsafe { Cap(capacity) } };
         Self { ptr: unsafe { Unique::new_unchecked(ptr) }, cap, alloc }
@@ -265,6 +267,7 @@ impl<T, A: Allocator> RawVec<T, A> {
     ///
     /// See [`RawVec::from_raw_parts_in`].
     #[inline]
+    #[safety::invariant]
     pub(crate) unsafe fn from_nonnull_in(ptr: NonNull<T>, capacity: usize, alloc: A) -> Self {
         let cap = if T::IS_ZST { Cap::ZERO } else { unsafe { Cap(capacity) } };
         Self { ptr: Unique::from(ptr), cap, alloc }
@@ -446,6 +449,7 @@ impl<T, A: Allocator> RawVec<T, A> {
     /// # Safety:
     ///
     /// `cap` must not exceed `isize::MAX`.
+    #[safety::invariant]
     unsafe fn set_ptr_and_cap(&mut self, ptr: NonNull<[u8]>, cap: usize) {
         // Allocators currently return a `NonNull<[u8]>` whose length matches
         // the size requested. If that ever changes, the capacity here should
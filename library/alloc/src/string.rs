@@ -3052,3 +3052,32 @@ impl From<char> for String {
         c.to_string()
     }
 }
+
+/// Creates a string of at most `MAX` bytes of arbitrary UTF-8, whose capacity is arbitrary too,
+/// for the harnesses that need an input of a symbolic size.
+#[cfg(any(kani, safety_random))]
+pub(crate) fn any_string_utf8<const MAX: usize>() -> String {
+    let bytes = crate::vec::any_vec::<u8, MAX>();
+    core::kani::assume(core::str::from_utf8(&bytes).is_ok());
+    // SAFETY: just checked that the bytes are valid UTF-8.
+    unsafe { String::from_utf8_unchecked(bytes) }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature="kani", issue="none")]
+mod verify {
+    use super::*;
+    use core::kani;
+
+    #[kani::proof]
+    #[kani::unwind(9)]
+    pub fn check_pop() {
+        let mut string = any_string_utf8::<8>();
+        let len = string.len();
+        match string.pop() {
+            Some(ch) => assert_eq!(string.len(), len - ch.len_utf8()),
+            None => assert_eq!(len, 0),
+        }
+        assert!(core::str::from_utf8(string.as_bytes()).is_ok());
+    }
+}
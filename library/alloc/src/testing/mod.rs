@@ -1,3 +1,4 @@
 pub mod crash_test;
 pub mod ord_chaos;
 pub mod rng;
+pub mod shadow_alloc;
@@ -0,0 +1,514 @@
+//! A test allocator that keeps track of its allocations, to report the misuses of the allocator
+//! API and to let the runtime contract predicates of `core` check the pointers into them.
+
+use core::alloc::{AllocError, Allocator, GlobalAlloc, Layout};
+use core::ptr::{self, NonNull};
+use core::ub_checks::{self, Shadow, ShadowMemory};
+use std::alloc::System;
+use std::cell::Cell;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+
+use crate::boxed::Box;
+use crate::vec::Vec;
+
+/// The number of bytes before and after each allocation that belong to no allocation, so that
+/// a pointer one byte past the end of an allocation is known to belong to it.
+const RED_ZONE: usize = 16;
+
+/// The total size of the freed allocations that are kept poisoned, so that they are not reused
+/// and the pointers into them can be told apart, before they are given back to the allocator.
+const QUARANTINE: usize = 1 << 24;
+
+/// The byte that the memory of freed allocations is filled with.
+const FREED: u8 = 0xdd;
+
+/// An allocator that records the live allocations it returns, their `Layout` and which of their
+/// bytes are initialized, on top of an inner allocator.
+///
+/// It panics on a double free, or when an allocation is deallocated, grown or shrunk with
+/// another layout than its own; as a `GlobalAlloc`, it aborts instead. Freed allocations are
+/// poisoned and kept aside for a while, and once it is installed with [`ShadowAlloc::install`],
+/// the runtime versions of predicates such as `ub_checks::can_dereference` reject the pointers
+/// into them, or out of bounds of live ones.
+///
+/// Newly allocated bytes are filled with a pattern that depends on their address, and a byte is
+/// known to be initialized once it no longer holds it. A value that has been written with the
+/// exact pattern is thus wrongly considered uninitialized, which is only likely for values of a
+/// single byte.
+pub struct ShadowAlloc<A = System> {
+    inner: A,
+    state: Mutex<State>,
+}
+
+struct State {
+    /// The live and quarantined allocations, sorted by address.
+    allocations: Vec<Allocation, System>,
+    /// The total size of the blocks of the quarantined allocations.
+    quarantined: usize,
+    /// The number of allocations so far, which orders frees and seeds the poison patterns.
+    count: u64,
+}
+
+struct Allocation {
+    /// The memory allocated from the inner allocator, which contains the allocation surrounded
+    /// by red zones.
+    base: *mut u8,
+    block: Layout,
+    /// The allocation, as returned to the caller.
+    start: *mut u8,
+    layout: Layout,
+    /// The seed of the pattern that the bytes are filled with when they are allocated.
+    seed: u64,
+    /// When the allocation was freed, if it is in quarantine.
+    freed: Option<u64>,
+    /// One bit per byte of the allocation, set if it is known to be initialized.
+    init: Vec<u64, System>,
+}
+
+// SAFETY: the memory of an allocation is only accessed while the state is locked, or by the owner
+// of the allocation.
+unsafe impl Send for Allocation {}
+
+/// A misuse of the allocator.
+#[derive(Debug)]
+enum Violation {
+    UnknownPointer,
+    DoubleFree,
+    LayoutMismatch { allocated: Layout },
+}
+
+thread_local! {
+    /// Whether the current thread has locked the state of a shadow allocator, in which case the
+    /// queries of the predicates of the code it runs are not answered, instead of deadlocking.
+    static LOCKED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The state of a shadow allocator, locked by the current thread.
+struct Locked<'a>(MutexGuard<'a, State>);
+
+impl Drop for Locked<'_> {
+    fn drop(&mut self) {
+        LOCKED.set(false);
+    }
+}
+
+/// The shadow allocator shared by the tests, which is installed the first time it is used.
+pub fn shadow() -> &'static ShadowAlloc {
+    static SHADOW: ShadowAlloc = ShadowAlloc::new(System);
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| SHADOW.install());
+    &SHADOW
+}
+
+impl<A> ShadowAlloc<A> {
+    /// Creates a shadow allocator whose allocations are made with `inner`.
+    pub const fn new(inner: A) -> Self {
+        let state = State { allocations: Vec::new_in(System), quarantined: 0, count: 0 };
+        ShadowAlloc { inner, state: Mutex::new(state) }
+    }
+
+    /// Makes the runtime contract predicates query this allocator about the pointers into its
+    /// allocations, instead of any previously installed one.
+    pub fn install(&'static self)
+    where
+        A: Sync,
+    {
+        let memory: &'static dyn ShadowMemory = self;
+        ub_checks::set_shadow_memory(Some(Box::leak(Box::new(memory))));
+    }
+
+    /// Locks the state, or returns `None` if the current thread already did.
+    fn lock(&self) -> Option<Locked<'_>> {
+        if LOCKED.replace(true) {
+            return None;
+        }
+        Some(Locked(self.state.lock().unwrap_or_else(PoisonError::into_inner)))
+    }
+
+    /// Locks the state to update it, which the current thread must not have locked already, since
+    /// the code that runs while it is locked does not allocate. This aborts instead of panicking,
+    /// as a `GlobalAlloc` must not unwind.
+    fn lock_for_update(&self) -> Locked<'_> {
+        self.lock().unwrap_or_else(|| {
+            std::eprintln!("ShadowAlloc: reentrant allocation");
+            std::process::abort()
+        })
+    }
+
+    /// Records an allocation of `layout`, whose block is allocated with `alloc`, and returns it,
+    /// or null if `alloc` fails.
+    fn track(
+        &self,
+        layout: Layout,
+        zeroed: bool,
+        alloc: impl FnOnce(Layout) -> *mut u8,
+    ) -> *mut u8 {
+        let Some((block, offset)) = block_layout(layout) else { return ptr::null_mut() };
+        let base = alloc(block);
+        if base.is_null() {
+            return base;
+        }
+        let mut state = self.lock_for_update();
+        state.0.count += 1;
+        let seed = state.0.count;
+        // SAFETY: the allocation is in the block, after its first red zone.
+        let start = unsafe { base.add(offset) };
+        let allocation = Allocation::new(base, block, start, layout, seed, zeroed);
+        let index = state.0.allocations.partition_point(|other| other.base < base);
+        state.0.allocations.insert(index, allocation);
+        start
+    }
+
+    /// Checks that `ptr` is a live allocation of `layout`.
+    fn check(&self, ptr: *mut u8, layout: Layout) -> Result<(), Violation> {
+        let state = self.lock_for_update();
+        state.0.live(ptr, layout).map(drop)
+    }
+
+    /// Frees the live allocation of `layout` at `ptr`, and gives the oldest quarantined blocks
+    /// back to the inner allocator with `dealloc` if there are too many.
+    fn untrack(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        mut dealloc: impl FnMut(*mut u8, Layout),
+    ) -> Result<(), Violation> {
+        let mut state = self.lock_for_update();
+        let state = &mut *state.0;
+        let index = state.live(ptr, layout)?;
+        state.count += 1;
+        let allocation = &mut state.allocations[index];
+        allocation.freed = Some(state.count);
+        // SAFETY: the allocation is valid for writes of its size, and no longer used.
+        unsafe { ptr::write_bytes(allocation.start, FREED, layout.size()) };
+        state.quarantined += allocation.block.size();
+        while state.quarantined > QUARANTINE {
+            let oldest = (0..state.allocations.len())
+                .filter(|&index| state.allocations[index].freed.is_some())
+                .min_by_key(|&index| state.allocations[index].freed)
+                .unwrap();
+            let allocation = state.allocations.remove(oldest);
+            state.quarantined -= allocation.block.size();
+            dealloc(allocation.base, allocation.block);
+        }
+        Ok(())
+    }
+
+    /// Moves the live allocation of `old` at `ptr` to a new one of `new`, allocated with
+    /// `alloc`, and frees the old one with `dealloc`. The initialized bytes of the old allocation
+    /// are still known to be initialized in the new one, and the others are poisoned again.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a live allocation of `old`, which is checked by [`ShadowAlloc::check`].
+    unsafe fn reallocate(
+        &self,
+        ptr: *mut u8,
+        old: Layout,
+        new: Layout,
+        zeroed: bool,
+        alloc: impl FnOnce(Layout) -> *mut u8,
+        dealloc: impl FnMut(*mut u8, Layout),
+    ) -> Result<*mut u8, Violation> {
+        let new_ptr = self.track(new, zeroed, alloc);
+        if new_ptr.is_null() {
+            return Ok(new_ptr);
+        }
+        let size = old.size().min(new.size());
+        // SAFETY: both allocations are valid for `size` bytes, and distinct.
+        unsafe { ptr::copy_nonoverlapping(ptr, new_ptr, size) };
+        {
+            let mut state = self.lock_for_update();
+            let allocations = &mut state.0.allocations;
+            let from = allocations.iter().position(|allocation| allocation.start == ptr).unwrap();
+            let to = allocations.iter().position(|allocation| allocation.start == new_ptr).unwrap();
+            // The bytes that were not written hold the poison of the old allocation.
+            for offset in 0..size {
+                if allocations[from].is_written(offset) {
+                    allocations[to].set_init(offset);
+                } else {
+                    allocations[to].set_uninit(offset);
+                }
+            }
+        }
+        self.untrack(ptr, old, dealloc)?;
+        Ok(new_ptr)
+    }
+}
+
+impl State {
+    /// The index of the allocation whose block contains `addr`.
+    fn find(&self, addr: usize) -> Option<usize> {
+        let index = self.allocations.partition_point(|allocation| allocation.base.addr() <= addr);
+        let allocation = self.allocations.get(index.checked_sub(1)?)?;
+        (addr < allocation.base.addr() + allocation.block.size()).then_some(index - 1)
+    }
+
+    /// The index of the live allocation of `layout` at `ptr`.
+    fn live(&self, ptr: *mut u8, layout: Layout) -> Result<usize, Violation> {
+        let index = self.find(ptr.addr()).ok_or(Violation::UnknownPointer)?;
+        let allocation = &self.allocations[index];
+        if allocation.start != ptr {
+            Err(Violation::UnknownPointer)
+        } else if allocation.freed.is_some() {
+            Err(Violation::DoubleFree)
+        } else if allocation.layout != layout {
+            Err(Violation::LayoutMismatch { allocated: allocation.layout })
+        } else {
+            Ok(index)
+        }
+    }
+}
+
+impl Allocation {
+    fn new(
+        base: *mut u8,
+        block: Layout,
+        start: *mut u8,
+        layout: Layout,
+        seed: u64,
+        zeroed: bool,
+    ) -> Self {
+        let mut init = Vec::new_in(System);
+        let word = if zeroed { u64::MAX } else { 0 };
+        init.resize(layout.size().div_ceil(64), word);
+        if !zeroed {
+            for offset in 0..layout.size() {
+                // SAFETY: the allocation is valid for writes of its size.
+                unsafe { start.add(offset).write(poison(seed, offset)) };
+            }
+        }
+        Allocation { base, block, start, layout, seed, freed: None, init }
+    }
+
+    fn is_init(&self, offset: usize) -> bool {
+        self.init[offset / 64] & (1 << (offset % 64)) != 0
+    }
+
+    fn set_init(&mut self, offset: usize) {
+        self.init[offset / 64] |= 1 << (offset % 64);
+    }
+
+    /// Marks the byte at `offset` as uninitialized, by filling it with its poison.
+    fn set_uninit(&mut self, offset: usize) {
+        self.init[offset / 64] &= !(1 << (offset % 64));
+        // SAFETY: the byte is in the live allocation.
+        unsafe { self.start.add(offset).write(poison(self.seed, offset)) };
+    }
+
+    /// Whether the byte at `offset` is initialized, which is learned once it no longer holds the
+    /// poison it was filled with.
+    fn is_written(&mut self, offset: usize) -> bool {
+        if self.is_init(offset) {
+            return true;
+        }
+        // SAFETY: the byte is in the live allocation, and was initialized when it was allocated.
+        let written = unsafe { self.start.add(offset).read() } != poison(self.seed, offset);
+        if written {
+            self.set_init(offset);
+        }
+        written
+    }
+}
+
+/// The byte that the byte at `offset` of the allocation with the given seed is filled with.
+fn poison(seed: u64, offset: usize) -> u8 {
+    (((seed << 32) ^ offset as u64).wrapping_mul(0x9e3779b97f4a7c15) >> 56) as u8
+}
+
+/// The layout of the block that contains an allocation of `layout` surrounded by red zones, and
+/// the offset of the allocation in it.
+fn block_layout(layout: Layout) -> Option<(Layout, usize)> {
+    let offset = RED_ZONE.max(layout.align());
+    let size = offset.checked_add(layout.size())?.checked_add(RED_ZONE)?;
+    Some((Layout::from_size_align(size, layout.align()).ok()?, offset))
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownPointer => f.write_str("unknown pointer"),
+            Violation::DoubleFree => f.write_str("double free"),
+            Violation::LayoutMismatch { allocated } => {
+                write!(f, "layout mismatch, the allocation has {allocated:?}")
+            }
+        }
+    }
+}
+
+#[track_caller]
+fn report(ptr: *mut u8, violation: Violation) -> ! {
+    panic!("ShadowAlloc: {violation} at {ptr:p}")
+}
+
+fn abort(ptr: *mut u8, violation: Violation) -> ! {
+    std::eprintln!("ShadowAlloc: {violation} at {ptr:p}");
+    std::process::abort()
+}
+
+impl<A: Sync> ShadowMemory for ShadowAlloc<A> {
+    fn lookup(&self, addr: usize) -> Shadow {
+        let Some(state) = self.lock() else { return Shadow::Untracked };
+        match state.0.find(addr).map(|index| &state.0.allocations[index]) {
+            None => Shadow::Untracked,
+            Some(allocation) if allocation.freed.is_some() => Shadow::Freed,
+            Some(allocation) => {
+                Shadow::Live { start: allocation.start.addr(), size: allocation.layout.size() }
+            }
+        }
+    }
+
+    fn is_initialized(&self, addr: usize, size: usize, count: usize) -> bool {
+        let Some(mut state) = self.lock() else { return true };
+        let Some(index) = state.0.find(addr) else { return true };
+        let allocation = &mut state.0.allocations[index];
+        // The accesses to freed allocations are reported by the other predicates.
+        if size == 0 || allocation.freed.is_some() {
+            return true;
+        }
+        let (start, end) =
+            (allocation.start.addr(), allocation.start.addr() + allocation.layout.size());
+        (0..count).all(|n| {
+            // Only the bytes in bounds of the allocation are known.
+            let Some(value) = n.checked_mul(size).and_then(|offset| addr.checked_add(offset))
+            else {
+                return true;
+            };
+            let (from, to) = (value.max(start), value.saturating_add(size).min(end));
+            from >= to || (from..to).any(|byte| allocation.is_written(byte - start))
+        })
+    }
+}
+
+// SAFETY: the allocations are the ones of the inner allocator, which stay valid until they are
+// deallocated.
+#[safety::contracts(from = core::ub_checks::contracts::Allocator)]
+unsafe impl<A: Allocator> Allocator for ShadowAlloc<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.track(layout, false, |block| {
+            self.inner.allocate(block).map_or(ptr::null_mut(), |ptr| ptr.cast().as_ptr())
+        });
+        NonNull::new(ptr)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(AllocError)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.track(layout, true, |block| {
+            self.inner.allocate_zeroed(block).map_or(ptr::null_mut(), |ptr| ptr.cast().as_ptr())
+        });
+        NonNull::new(ptr)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the blocks were allocated by the inner allocator.
+        let dealloc =
+            |base, block| unsafe { self.inner.deallocate(NonNull::new_unchecked(base), block) };
+        if let Err(violation) = self.untrack(ptr.as_ptr(), layout, dealloc) {
+            report(ptr.as_ptr(), violation);
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the caller upholds the same requirements.
+        unsafe { self.move_to(ptr, old_layout, new_layout, false) }
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the caller upholds the same requirements.
+        unsafe { self.move_to(ptr, old_layout, new_layout, true) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the caller upholds the same requirements.
+        unsafe { self.move_to(ptr, old_layout, new_layout, false) }
+    }
+}
+
+impl<A: Allocator> ShadowAlloc<A> {
+    /// Implements `grow`, `grow_zeroed` and `shrink`, by moving the allocation to a new one.
+    unsafe fn move_to(
+        &self,
+        ptr: NonNull<u8>,
+        old: Layout,
+        new: Layout,
+        zeroed: bool,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = ptr.as_ptr();
+        if let Err(violation) = self.check(ptr, old) {
+            report(ptr, violation);
+        }
+        let alloc = |block| {
+            let ptr =
+                if zeroed { self.inner.allocate_zeroed(block) } else { self.inner.allocate(block) };
+            ptr.map_or(ptr::null_mut(), |ptr| ptr.cast().as_ptr())
+        };
+        // SAFETY: the blocks were allocated by the inner allocator.
+        let dealloc =
+            |base, block| unsafe { self.inner.deallocate(NonNull::new_unchecked(base), block) };
+        // SAFETY: `ptr` was just checked to be a live allocation of `old`.
+        match unsafe { self.reallocate(ptr, old, new, zeroed, alloc, dealloc) } {
+            Ok(new_ptr) => NonNull::new(new_ptr)
+                .map(|new_ptr| NonNull::slice_from_raw_parts(new_ptr, new.size()))
+                .ok_or(AllocError),
+            Err(violation) => report(ptr, violation),
+        }
+    }
+}
+
+// SAFETY: the allocations are the ones of the inner allocator, which stay valid until they are
+// deallocated, and this aborts instead of unwinding.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for ShadowAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the blocks are never zero-sized, since they contain red zones.
+        self.track(layout, false, |block| unsafe { self.inner.alloc(block) })
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the blocks are never zero-sized, since they contain red zones.
+        self.track(layout, true, |block| unsafe { self.inner.alloc_zeroed(block) })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the blocks were allocated by the inner allocator.
+        let dealloc = |base, block| unsafe { self.inner.dealloc(base, block) };
+        if let Err(violation) = self.untrack(ptr, layout, dealloc) {
+            abort(ptr, violation);
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if let Err(violation) = self.check(ptr, layout) {
+            abort(ptr, violation);
+        }
+        // SAFETY: the caller guarantees that the new size is valid for the alignment.
+        let new = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+        // SAFETY: the blocks are never zero-sized, since they contain red zones.
+        let alloc = |block| unsafe { self.inner.alloc(block) };
+        // SAFETY: the blocks were allocated by the inner allocator.
+        let dealloc = |base, block| unsafe { self.inner.dealloc(base, block) };
+        // SAFETY: `ptr` was just checked to be a live allocation of `layout`.
+        match unsafe { self.reallocate(ptr, layout, new, false, alloc, dealloc) } {
+            Ok(new_ptr) => new_ptr,
+            Err(violation) => abort(ptr, violation),
+        }
+    }
+}
@@ -146,6 +146,9 @@ use self::spec_extend::SpecExtend;
 #[cfg(not(no_global_oom_handling))]
 mod spec_extend;
 
+#[cfg(test)]
+mod tests;
+
 /// A contiguous growable array type, written as `Vec<T>`, short for 'vector'.
 ///
 /// # Examples
@@ -3556,3 +3559,38 @@ impl<T, A: Allocator, const N: usize> TryFrom<Vec<T, A>> for [T; N] {
         Ok(array)
     }
 }
+
+/// Creates a vector of at most `MAX` arbitrary elements, whose length and capacity are arbitrary
+/// too, for the harnesses that need an input of a symbolic size.
+#[cfg(any(kani, safety_random))]
+pub(crate) fn any_vec<T: core::kani::Arbitrary, const MAX: usize>() -> Vec<T> {
+    let capacity = core::kani::any_where(|capacity: &usize| *capacity <= MAX);
+    let len = core::kani::any_where(|len: &usize| *len <= capacity);
+    let mut vec = Vec::with_capacity(capacity);
+    for i in 0..MAX {
+        if i < len {
+            vec.push(core::kani::any());
+        }
+    }
+    vec
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature="kani", issue="none")]
+mod verify {
+    use super::*;
+    use core::kani;
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    pub fn check_drain() {
+        let mut vec = any_vec::<u8, 4>();
+        let len = vec.len();
+        let start = kani::any_where(|start: &usize| *start <= len);
+        let end = kani::any_where(|end: &usize| start <= *end && *end <= len);
+        let tail = vec[end..].to_vec();
+        assert_eq!(vec.drain(start..end).count(), end - start);
+        assert_eq!(vec.len(), len - (end - start));
+        assert_eq!(vec[start..], tail[..]);
+    }
+}
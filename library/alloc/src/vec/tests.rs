@@ -0,0 +1,37 @@
+use super::*;
+use crate::testing::shadow_alloc::shadow;
+use core::alloc::Layout;
+
+#[test]
+fn test_shadow_alloc() {
+    let mut v = Vec::new_in(shadow());
+    for i in 0..100 {
+        v.push(i);
+    }
+    let drained: Vec<_> = v.drain(10..90).collect();
+    assert_eq!(drained, (10..90).collect::<Vec<_>>());
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 20);
+    v.extend_from_within(..5);
+    v.insert(0, 100);
+    assert_eq!(v[..6], [100, 0, 1, 2, 3, 4]);
+    assert_eq!(v[21..], [0, 1, 2, 3, 4]);
+}
+
+#[test]
+#[cfg_attr(not(safety_random), ignore = "a violated precondition only unwinds under safety_random")]
+#[should_panic(expected = "unsafe precondition(s) violated")]
+fn test_shadow_alloc_use_after_free() {
+    let mut v = Vec::with_capacity_in(4, shadow());
+    v.extend([1, 2, 3]);
+    let (ptr, len, capacity, alloc) = v.into_raw_parts_with_alloc();
+    let layout = Layout::array::<i32>(capacity).unwrap();
+    // The vector is not dropped, since its buffer is already freed.
+    let mut v = ManuallyDrop::new(unsafe {
+        alloc.deallocate(NonNull::new_unchecked(ptr.cast()), layout);
+        Vec::from_raw_parts_in(ptr, len, capacity, alloc)
+    });
+    // There is room for the new element, so the elements are shifted in the freed buffer, which
+    // the installed shadow allocator makes the preconditions of the pointer operations reject.
+    v.insert(0, 0);
+}
@@ -0,0 +1,352 @@
+//! Parse the tables of functions of the challenges, and find the functions of the library they
+//! refer to.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use syn::TypePath;
+
+use crate::source::{type_name, Function, Harness, Inventory};
+
+/// A challenge of the book.
+pub struct Challenge {
+    /// The name of the file of the challenge, such as `0001-core-transmutation`.
+    pub id: String,
+    pub title: String,
+    pub entries: Vec<Entry>,
+}
+
+/// A row of one of the `| Function | Location |` tables of a challenge.
+pub struct Entry {
+    pub function: String,
+    pub location: String,
+}
+
+impl Challenge {
+    pub fn parse(path: &Path) -> io::Result<Challenge> {
+        let text = fs::read_to_string(path)?;
+        let id = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        Ok(Challenge::from_markdown(id, &text))
+    }
+
+    fn from_markdown(id: String, text: &str) -> Challenge {
+        let title =
+            text.lines().find_map(|line| line.strip_prefix("# ")).unwrap_or(&id).trim().to_string();
+        let mut entries = Vec::new();
+        // The column of the function and of the location in the current table, if any.
+        let mut columns = None;
+        for line in text.lines() {
+            let line = line.trim();
+            if !line.starts_with('|') {
+                columns = None;
+                continue;
+            }
+            let cells: Vec<_> = line.trim_matches('|').split('|').map(clean).collect();
+            if cells.iter().all(|cell| cell.chars().all(|c| c == '-' || c == ':')) {
+                continue;
+            }
+            match columns {
+                None => {
+                    let position =
+                        |name| cells.iter().position(|cell| cell.eq_ignore_ascii_case(name));
+                    columns = position("function").zip(position("location"));
+                }
+                Some((function, location)) => {
+                    if let (Some(function), Some(location)) =
+                        (cells.get(function), cells.get(location))
+                    {
+                        entries
+                            .push(Entry { function: function.clone(), location: location.clone() });
+                    }
+                }
+            }
+        }
+        Challenge { id, title, entries }
+    }
+}
+
+fn clean(cell: &str) -> String {
+    cell.trim().trim_matches('`').trim().to_string()
+}
+
+/// The path of a function as written in a challenge or in a harness, such as `swap`,
+/// `Layout::from_size_align` or `<char as Step>::forward_checked`.
+pub struct Target {
+    self_ty: Option<String>,
+    trait_name: Option<String>,
+    name: String,
+}
+
+impl Target {
+    pub fn parse(path: &str) -> Option<Target> {
+        let path: TypePath = syn::parse_str(path).ok()?;
+        let segments = &path.path.segments;
+        let name = segments.last()?.ident.to_string();
+        let (self_ty, trait_name) = match &path.qself {
+            Some(qself) => {
+                let trait_name =
+                    (qself.position > 0).then(|| segments[qself.position - 1].ident.to_string());
+                (Some(type_name(&qself.ty)), trait_name)
+            }
+            None if segments.len() > 1 => {
+                (Some(segments[segments.len() - 2].ident.to_string()), None)
+            }
+            None => (None, None),
+        };
+        Some(Target { self_ty, trait_name, name })
+    }
+
+    fn matches(&self, function: &Function) -> bool {
+        function.name == self.name
+            && (self.self_ty.is_none() || function.self_ty == self.self_ty)
+            && (self.trait_name.is_none() || function.trait_name == self.trait_name)
+    }
+
+    /// The functions that the path may refer to.
+    ///
+    /// Paths are not resolved: a method is looked up by the name of its type and of its trait,
+    /// and a function by its name. If a path has a type but no method matches, it is looked up
+    /// as a function in a module named after the type, such as `char::encode_utf16_raw`. A path
+    /// without a type prefers functions over methods.
+    fn candidates<'a>(&self, inventory: &'a Inventory) -> Vec<&'a Function> {
+        let mut candidates: Vec<_> =
+            inventory.functions.iter().filter(|function| self.matches(function)).collect();
+        if let (true, Some(self_ty)) = (candidates.is_empty(), &self.self_ty) {
+            let module = self_ty.to_lowercase();
+            candidates = inventory
+                .functions
+                .iter()
+                .filter(|function| {
+                    function.name == self.name
+                        && function.self_ty.is_none()
+                        && function.module.contains(&module)
+                })
+                .collect();
+        }
+        if self.self_ty.is_none() && candidates.iter().any(|function| function.self_ty.is_none()) {
+            candidates.retain(|function| function.self_ty.is_none());
+        }
+        candidates
+    }
+}
+
+/// The coverage of an entry of a challenge.
+pub struct Coverage<'a> {
+    pub entry: &'a Entry,
+    /// The functions that the entry refers to.
+    pub functions: Vec<&'a Function>,
+    /// The harnesses that verify one of the functions.
+    pub harnesses: Vec<&'a Harness>,
+}
+
+impl Coverage<'_> {
+    /// Whether the entry refers to several functions that its location cannot tell apart. Such an
+    /// entry is neither annotated nor verified, since it is unknown which function it means.
+    pub fn ambiguous(&self) -> bool {
+        self.functions.len() > 1
+    }
+
+    pub fn annotated(&self) -> bool {
+        !self.ambiguous() && self.functions.iter().any(|function| !function.contract.is_empty())
+    }
+
+    pub fn verified(&self) -> bool {
+        !self.ambiguous() && !self.harnesses.is_empty()
+    }
+}
+
+impl Entry {
+    /// Find the functions that the entry refers to, and the harnesses that verify them.
+    pub fn coverage<'a>(&'a self, inventory: &'a Inventory) -> Coverage<'a> {
+        let mut functions = Target::parse(&self.function)
+            .map(|target| target.candidates(inventory))
+            .unwrap_or_default();
+        // The locations are only used to choose between functions of the same name, since some
+        // of them are approximate, as `core::intrisics`, or name the crate that re-exports the
+        // function.
+        let location: Vec<_> = self.location.split("::").collect();
+        let in_crate = |function: &&Function| function.module[0] == location[0];
+        if functions.iter().any(in_crate) {
+            functions.retain(in_crate);
+        }
+        let in_location = |function: &&Function| {
+            let mut module = function.module.iter();
+            location[1..].iter().all(|segment| module.any(|part| part == segment))
+        };
+        if functions.iter().any(in_location) {
+            functions.retain(in_location);
+        }
+        let harnesses = inventory
+            .harnesses
+            .iter()
+            .filter(|harness| {
+                verified_by(harness, inventory).iter().any(|verified| {
+                    functions.iter().any(|function| std::ptr::eq(*function, *verified))
+                })
+            })
+            .collect();
+        Coverage { entry: self, functions, harnesses }
+    }
+}
+
+/// The functions that a harness may verify: the ones of its crate that its target can refer to,
+/// and that are the closest to the module of the harness.
+fn verified_by<'a>(harness: &Harness, inventory: &'a Inventory) -> Vec<&'a Function> {
+    let Some(target) = Target::parse(&harness.target) else { return Vec::new() };
+    let candidates = target.candidates(inventory);
+    let common_prefix = |function: &Function| {
+        function.module.iter().zip(&harness.module).take_while(|(a, b)| a == b).count()
+    };
+    let closest = candidates.iter().map(|function| common_prefix(function)).max().unwrap_or(0);
+    if closest == 0 {
+        return Vec::new();
+    }
+    candidates.into_iter().filter(|function| common_prefix(function) == closest).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn function(
+        module: &str,
+        self_ty: Option<&str>,
+        trait_name: Option<&str>,
+        name: &str,
+    ) -> Function {
+        Function {
+            module: module.split("::").map(String::from).collect(),
+            self_ty: self_ty.map(String::from),
+            trait_name: trait_name.map(String::from),
+            name: name.to_string(),
+            file: PathBuf::new(),
+            line: 0,
+            contract: vec!["requires".to_string()],
+            trusted: None,
+        }
+    }
+
+    fn entry(function: &str, location: &str) -> Entry {
+        Entry { function: function.to_string(), location: location.to_string() }
+    }
+
+    fn displays(functions: &[&Function]) -> Vec<String> {
+        functions.iter().map(|function| function.display()).collect()
+    }
+
+    #[test]
+    fn function_tables() {
+        let text = "\
+# Challenge 1: Verify the transmutations
+
+| Function | Location |
+|:---------|:--------:|
+| `swap` | `core::ptr` |
+|\t`Layout::from_size_align`\t|\tcore::alloc\t|
+
+Some text.
+
+| Safety | Function | Location |
+|--------|----------|----------|
+| unsafe | `<char as Step>::forward_checked` | `core::iter` |
+
+| Name | Value |
+|------|-------|
+| `read` | `core::ptr` |
+";
+        let challenge = Challenge::from_markdown("0001-test".to_string(), text);
+        assert_eq!(challenge.title, "Challenge 1: Verify the transmutations");
+        let entries: Vec<_> = challenge
+            .entries
+            .iter()
+            .map(|entry| (entry.function.as_str(), entry.location.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("swap", "core::ptr"),
+                ("Layout::from_size_align", "core::alloc"),
+                ("<char as Step>::forward_checked", "core::iter"),
+            ]
+        );
+    }
+
+    #[test]
+    fn target_paths() {
+        let parse = |path| {
+            let target = Target::parse(path).unwrap();
+            (target.self_ty, target.trait_name, target.name)
+        };
+        let some = |name: &str| Some(name.to_string());
+        assert_eq!(parse("swap"), (None, None, "swap".to_string()));
+        assert_eq!(
+            parse("Layout::from_size_align"),
+            (some("Layout"), None, "from_size_align".to_string())
+        );
+        assert_eq!(
+            parse("<char as Step>::forward_checked"),
+            (some("char"), some("Step"), "forward_checked".to_string())
+        );
+        assert!(Target::parse("not a path").is_none());
+    }
+
+    #[test]
+    fn candidates() {
+        let inventory = Inventory {
+            functions: vec![
+                function("core::ptr", None, None, "swap"),
+                function("core::slice", Some("[T]"), None, "swap"),
+                function("core::char::methods", None, None, "encode_utf16_raw"),
+                function("core::iter::range", Some("char"), Some("Step"), "forward_checked"),
+                function("core::iter::range", Some("u8"), Some("Step"), "forward_checked"),
+            ],
+            ..Inventory::default()
+        };
+        let candidates = |path| displays(&Target::parse(path).unwrap().candidates(&inventory));
+        assert_eq!(candidates("swap"), ["core::ptr::swap"]);
+        assert_eq!(candidates("char::encode_utf16_raw"), ["core::char::methods::encode_utf16_raw"]);
+        assert_eq!(
+            candidates("<char as Step>::forward_checked"),
+            ["core::iter::range::<char as Step>::forward_checked"]
+        );
+        assert_eq!(candidates("Step::forward_checked").len(), 0);
+        assert_eq!(candidates("forward_checked").len(), 2);
+    }
+
+    #[test]
+    fn ambiguous_entries() {
+        let inventory = Inventory {
+            functions: vec![
+                function("core::fmt", Some("Formatter"), None, "write_bytes"),
+                function("core::intrinsics", None, None, "write_bytes"),
+                function("core::intrinsics::write_bytes", None, None, "write_bytes"),
+                function("core::ptr", None, None, "swap"),
+                function("core::mem", None, None, "swap"),
+            ],
+            harnesses: vec![Harness {
+                module: vec!["core".to_string(), "ptr".to_string(), "verify".to_string()],
+                name: "check_swap".to_string(),
+                target: "swap".to_string(),
+                file: PathBuf::new(),
+                line: 0,
+            }],
+            ..Inventory::default()
+        };
+
+        let write_bytes = entry("write_bytes", "core::intrisics");
+        let coverage = write_bytes.coverage(&inventory);
+        assert_eq!(
+            displays(&coverage.functions),
+            ["core::intrinsics::write_bytes", "core::intrinsics::write_bytes::write_bytes"]
+        );
+        assert!(coverage.ambiguous() && !coverage.annotated() && !coverage.verified());
+
+        let swap = entry("swap", "core::ptr");
+        let coverage = swap.coverage(&inventory);
+        assert_eq!(displays(&coverage.functions), ["core::ptr::swap"]);
+        assert!(!coverage.ambiguous() && coverage.annotated() && coverage.verified());
+    }
+}
@@ -0,0 +1,94 @@
+//! Report which functions of the challenges already have contracts and harnesses, written with
+//! `proof_for_contract` or generated by `auto_harness`.
+//!
+//! The sources of `core`, `alloc` and `std` are parsed to find the functions that have a
+//! `requires`, `ensures`, `modifies` or `trusted` attribute, or that inherit a contract through
+//! `contracts`, and the harnesses that verify them. They are then matched against the
+//! `| Function | Location |` tables of each challenge of the book. A function is annotated if it
+//! has a contract, and verified if a harness targets it.
+//! The justification of a `trusted` function is reported with its contract.
+//!
+//! ```text
+//! cargo run -- [--library <dir>] [--challenges <dir>] [--out <dir>]
+//! ```
+//!
+//! The summary of each challenge is printed. With `--out`, a `<challenge>.json` and a
+//! `<challenge>.md` report are written to the directory for each challenge.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use challenge::Challenge;
+use report::Report;
+use source::Inventory;
+
+mod challenge;
+mod report;
+mod source;
+
+const CRATES: &[&str] = &["core", "alloc", "std"];
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut library = manifest_dir.join("../..");
+    let mut challenges = manifest_dir.join("../../../doc/src/challenges");
+    let mut out = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().map(PathBuf::from).ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--library" => library = value()?,
+            "--challenges" => challenges = value()?,
+            "--out" => out = Some(value()?),
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    let library = library.canonicalize().map_err(|err| format!("{}: {err}", library.display()))?;
+
+    let inventory = Inventory::scan(&library, CRATES);
+    for (file, reason) in &inventory.skipped {
+        eprintln!("warning: skipped {}: {reason}", file.display());
+    }
+
+    let mut paths: Vec<_> = fs::read_dir(&challenges)
+        .map_err(|err| format!("{}: {err}", challenges.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+    if let Some(out) = &out {
+        fs::create_dir_all(out).map_err(|err| format!("{}: {err}", out.display()))?;
+    }
+    for path in paths {
+        let challenge =
+            Challenge::parse(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        if challenge.entries.is_empty() {
+            continue;
+        }
+        let coverage = challenge.entries.iter().map(|entry| entry.coverage(&inventory)).collect();
+        let report = Report { challenge: &challenge, coverage };
+        println!("{}: {}", challenge.id, report.summary());
+        if let Some(out) = &out {
+            let json = serde_json::to_string_pretty(&report.json(&library)).unwrap();
+            write(out.join(format!("{}.json", challenge.id)), json + "\n")?;
+            write(out.join(format!("{}.md", challenge.id)), report.markdown(&library))?;
+        }
+    }
+    Ok(())
+}
+
+fn write(path: PathBuf, contents: String) -> Result<(), String> {
+    fs::write(&path, contents).map_err(|err| format!("{}: {err}", path.display()))
+}
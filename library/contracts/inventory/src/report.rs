@@ -0,0 +1,127 @@
+//! Render the coverage of a challenge as JSON and as markdown.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::challenge::{Challenge, Coverage};
+use crate::source::Function;
+
+pub struct Report<'a> {
+    pub challenge: &'a Challenge,
+    pub coverage: Vec<Coverage<'a>>,
+}
+
+impl Report<'_> {
+    pub fn annotated(&self) -> usize {
+        self.coverage.iter().filter(|coverage| coverage.annotated()).count()
+    }
+
+    pub fn verified(&self) -> usize {
+        self.coverage.iter().filter(|coverage| coverage.verified()).count()
+    }
+
+    pub fn ambiguous(&self) -> usize {
+        self.coverage.iter().filter(|coverage| coverage.ambiguous()).count()
+    }
+
+    /// The one line summary, such as `31/47 annotated, 22/47 verified, 2/47 ambiguous`.
+    pub fn summary(&self) -> String {
+        let total = self.coverage.len();
+        let (annotated, verified, ambiguous) =
+            (self.annotated(), self.verified(), self.ambiguous());
+        format!("{annotated}/{total} annotated, {verified}/{total} verified, {ambiguous}/{total} ambiguous")
+    }
+
+    pub fn json(&self, library: &Path) -> Value {
+        let functions: Vec<_> = self
+            .coverage
+            .iter()
+            .map(|coverage| {
+                json!({
+                    "function": coverage.entry.function,
+                    "location": coverage.entry.location,
+                    "annotated": coverage.annotated(),
+                    "verified": coverage.verified(),
+                    "ambiguous": coverage.ambiguous(),
+                    "definitions": coverage
+                        .functions
+                        .iter()
+                        .map(|function| json!({
+                            "path": function.display(),
+                            "source": source(library, function),
+                            "contract": function.contract,
+                            "trusted": function.trusted,
+                        }))
+                        .collect::<Vec<_>>(),
+                    "harnesses": coverage
+                        .harnesses
+                        .iter()
+                        .map(|harness| json!({
+                            "path": format!("{}::{}", harness.module.join("::"), harness.name),
+                            "source": location(library, &harness.file, harness.line),
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        json!({
+            "challenge": self.challenge.id,
+            "title": self.challenge.title,
+            "total": self.coverage.len(),
+            "annotated": self.annotated(),
+            "verified": self.verified(),
+            "ambiguous": self.ambiguous(),
+            "functions": functions,
+        })
+    }
+
+    pub fn markdown(&self, library: &Path) -> String {
+        let mut out = String::new();
+        writeln!(out, "# {}", self.challenge.title).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "{}", self.summary()).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "| Function | Location | Annotated | Verified | Definition |").unwrap();
+        writeln!(out, "|----------|----------|-----------|----------|------------|").unwrap();
+        for coverage in &self.coverage {
+            let definitions = if coverage.functions.is_empty() {
+                "not found".to_string()
+            } else {
+                let sources = coverage.functions.iter().map(|function| source(library, function));
+                sources.map(|source| format!("`{source}`")).collect::<Vec<_>>().join("<br>")
+            };
+            writeln!(
+                out,
+                "| `{}` | `{}` | {} | {} | {definitions} |",
+                coverage.entry.function,
+                coverage.entry.location,
+                mark(coverage, coverage.annotated()),
+                mark(coverage, coverage.verified()),
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+fn mark(coverage: &Coverage<'_>, done: bool) -> &'static str {
+    if coverage.ambiguous() {
+        "ambiguous"
+    } else if done {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// The location of a function, relative to the library.
+fn source(library: &Path, function: &Function) -> String {
+    location(library, &function.file, function.line)
+}
+
+fn location(library: &Path, file: &Path, line: usize) -> String {
+    let file = file.strip_prefix(library).unwrap_or(file);
+    format!("{}:{line}", file.display())
+}
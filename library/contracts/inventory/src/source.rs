@@ -0,0 +1,399 @@
+//! Collect the functions of the library, with the contracts they declare, and the harnesses that
+//! verify them.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use quote::ToTokens;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, Expr, ExprLit, ForeignItemFn, ImplItemFn, ItemFn, ItemImpl, ItemMod, ItemTrait, Lit,
+    MetaNameValue, Signature, TraitItemFn, Type, TypePath,
+};
+
+/// A function or method of the library.
+pub struct Function {
+    /// The path of the module that defines the function, starting with the name of the crate.
+    pub module: Vec<String>,
+    /// The name of the type of the `impl` block that defines the method, if any.
+    pub self_ty: Option<String>,
+    /// The name of the trait that the method implements, if any.
+    pub trait_name: Option<String>,
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+    /// The clauses of the contract of the function, such as `requires`.
+    pub contract: Vec<String>,
+    /// The justification of the `trusted` attribute, if the contract of the function is assumed.
+    pub trusted: Option<String>,
+}
+
+impl Function {
+    /// The path of the function as it would be written in `core`, such as
+    /// `core::ptr::<*const T>::is_aligned_to`.
+    pub fn display(&self) -> String {
+        let owner = match (&self.self_ty, &self.trait_name) {
+            (Some(ty), Some(trait_name)) => format!("<{ty} as {trait_name}>::"),
+            (Some(ty), None) => format!("{ty}::"),
+            (None, Some(trait_name)) => format!("{trait_name}::"),
+            (None, None) => String::new(),
+        };
+        format!("{}::{owner}{}", self.module.join("::"), self.name)
+    }
+}
+
+/// A `proof_for_contract` harness, or the harnesses that `auto_harness` generates for a function,
+/// which are named after the module that contains them.
+pub struct Harness {
+    pub module: Vec<String>,
+    pub name: String,
+    /// The path of the function it verifies, as written in the attribute.
+    pub target: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Default)]
+pub struct Inventory {
+    pub functions: Vec<Function>,
+    pub harnesses: Vec<Harness>,
+    /// The files that could not be parsed, with the reason.
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl Inventory {
+    /// Scan the sources of the given crates of the library.
+    pub fn scan(library: &Path, crates: &[&str]) -> Inventory {
+        let mut inventory = Inventory::default();
+        let mut contract_traits = HashSet::new();
+        let mut inheriting = Vec::new();
+        for krate in crates {
+            let root = library.join(krate).join("src");
+            let mut files = Vec::new();
+            collect_files(&root, &mut files);
+            files.sort();
+            for file in files {
+                let source = match fs::read_to_string(&file) {
+                    Ok(source) => source,
+                    Err(err) => {
+                        inventory.skipped.push((file, err.to_string()));
+                        continue;
+                    }
+                };
+                let ast = match syn::parse_file(&source) {
+                    Ok(ast) => ast,
+                    Err(err) => {
+                        let start = err.span().start();
+                        let reason = format!("{}:{}: {err}", start.line, start.column);
+                        inventory.skipped.push((file, reason));
+                        continue;
+                    }
+                };
+                let mut visitor = Visitor {
+                    file: &file,
+                    module: module_path(krate, &root, &file),
+                    impls: Vec::new(),
+                    inventory: &mut inventory,
+                    contract_traits: &mut contract_traits,
+                    inheriting: &mut inheriting,
+                };
+                visitor.visit_file(&ast);
+            }
+        }
+        // The methods of a `contracts` impl inherit the contract of the trait methods they
+        // implement, which may be declared in a file that was scanned after the impl.
+        for idx in inheriting {
+            let function = &mut inventory.functions[idx];
+            let key = (function.trait_name.clone().unwrap_or_default(), function.name.clone());
+            if contract_traits.contains(&key) {
+                function.contract.push("inherited".to_string());
+            }
+        }
+        inventory
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// The path of the module of a file, assuming the usual layout of modules.
+fn module_path(krate: &str, root: &Path, file: &Path) -> Vec<String> {
+    let mut module = vec![krate.to_string()];
+    let relative = file.strip_prefix(root).unwrap_or(file).with_extension("");
+    module.extend(relative.iter().map(|part| part.to_string_lossy().into_owned()));
+    if matches!(module.last().map(String::as_str), Some("mod" | "lib")) {
+        module.pop();
+    }
+    module
+}
+
+/// The `impl` block or trait that a method is defined in.
+struct Owner {
+    self_ty: Option<String>,
+    trait_name: Option<String>,
+    /// Whether the block has the `contracts` attribute.
+    contracts: bool,
+}
+
+struct Visitor<'a> {
+    file: &'a Path,
+    module: Vec<String>,
+    impls: Vec<Owner>,
+    inventory: &'a mut Inventory,
+    /// The methods of traits with the `contracts` attribute that declare a contract.
+    contract_traits: &'a mut HashSet<(String, String)>,
+    /// The indices of the methods of `contracts` impls.
+    inheriting: &'a mut Vec<usize>,
+}
+
+impl Visitor<'_> {
+    fn add_function(&mut self, attrs: &[Attribute], sig: &Signature) {
+        let owner = self.impls.last();
+        let contract: Vec<_> = attrs
+            .iter()
+            .filter_map(|attr| {
+                let name = attr_name(attr)?;
+                ["requires", "ensures", "modifies", "trusted"].contains(&name).then(|| name.into())
+            })
+            .collect();
+        if let Some(Owner { trait_name: Some(trait_name), self_ty: None, contracts: true }) = owner
+        {
+            if !contract.is_empty() {
+                self.contract_traits.insert((trait_name.clone(), sig.ident.to_string()));
+            }
+        }
+        if owner.is_some_and(|owner| owner.contracts && owner.self_ty.is_some()) {
+            self.inheriting.push(self.inventory.functions.len());
+        }
+        let trusted = attrs.iter().find_map(|attr| match attr_name(attr) {
+            Some("trusted") => trusted_reason(attr),
+            _ => None,
+        });
+        for attr in attrs {
+            match attr_name(attr) {
+                Some("proof_for_contract") => {
+                    if let Ok(target) = attr.parse_args::<TypePath>() {
+                        let harness =
+                            self.harness(sig.ident.to_string(), render_path(&target), sig);
+                        self.inventory.harnesses.push(harness);
+                    }
+                }
+                // The harnesses that `auto_harness` generates are in a module named after the
+                // function, and verify it.
+                Some("auto_harness") => {
+                    let name = format!("__safety_harnesses_{}", sig.ident);
+                    let harness = self.harness(name, sig.ident.to_string(), sig);
+                    self.inventory.harnesses.push(harness);
+                }
+                _ => {}
+            }
+        }
+        self.inventory.functions.push(Function {
+            module: self.module.clone(),
+            self_ty: owner.and_then(|owner| owner.self_ty.clone()),
+            trait_name: owner.and_then(|owner| owner.trait_name.clone()),
+            name: sig.ident.to_string(),
+            file: self.file.to_path_buf(),
+            line: sig.ident.span().start().line,
+            contract,
+            trusted,
+        });
+    }
+
+    fn harness(&self, name: String, target: String, sig: &Signature) -> Harness {
+        Harness {
+            module: self.module.clone(),
+            name,
+            target,
+            file: self.file.to_path_buf(),
+            line: sig.ident.span().start().line,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Visitor<'_> {
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        self.module.push(item.ident.to_string());
+        visit::visit_item_mod(self, item);
+        self.module.pop();
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        // Functions nested in a function do not belong to an impl block.
+        let impls = std::mem::take(&mut self.impls);
+        self.add_function(&item.attrs, &item.sig);
+        visit::visit_item_fn(self, item);
+        self.impls = impls;
+    }
+
+    fn visit_foreign_item_fn(&mut self, item: &'ast ForeignItemFn) {
+        self.add_function(&item.attrs, &item.sig);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        self.impls.push(Owner {
+            self_ty: Some(type_name(&item.self_ty)),
+            trait_name: item
+                .trait_
+                .as_ref()
+                .map(|(_, path, _)| path.segments.last().unwrap().ident.to_string()),
+            contracts: has_contracts(&item.attrs),
+        });
+        visit::visit_item_impl(self, item);
+        self.impls.pop();
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        self.add_function(&item.attrs, &item.sig);
+        visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast ItemTrait) {
+        self.impls.push(Owner {
+            self_ty: None,
+            trait_name: Some(item.ident.to_string()),
+            contracts: has_contracts(&item.attrs),
+        });
+        visit::visit_item_trait(self, item);
+        self.impls.pop();
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast TraitItemFn) {
+        self.add_function(&item.attrs, &item.sig);
+        visit::visit_trait_item_fn(self, item);
+    }
+}
+
+/// The name of a contract attribute, or of a Kani attribute.
+fn attr_name(attr: &Attribute) -> Option<&'static str> {
+    let path = attr.path();
+    let name = &path.segments.last()?.ident;
+    let is_ours = path.segments.len() == 1
+        || path.segments.len() == 2
+            && (path.segments[0].ident == "safety" || path.segments[0].ident == "kani");
+    let names = [
+        "requires",
+        "ensures",
+        "modifies",
+        "trusted",
+        "contracts",
+        "proof_for_contract",
+        "auto_harness",
+    ];
+    names.into_iter().find(|candidate| is_ours && name == candidate)
+}
+
+/// The `reason = "..."` of a `trusted` attribute.
+fn trusted_reason(attr: &Attribute) -> Option<String> {
+    let MetaNameValue { path, value, .. } = attr.parse_args().ok()?;
+    match value {
+        Expr::Lit(ExprLit { lit: Lit::Str(reason), .. }) if path.is_ident("reason") => {
+            Some(reason.value())
+        }
+        _ => None,
+    }
+}
+
+fn has_contracts(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr_name(attr) == Some("contracts"))
+}
+
+/// The name that identifies a type in the challenges: the name of a path without its generic
+/// arguments, `[T]` for slices, `[T; N]` for arrays, and `*const T` or `*mut T` for pointers.
+pub fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => path.path.segments.last().unwrap().ident.to_string(),
+        Type::Slice(_) => "[T]".to_string(),
+        Type::Array(_) => "[T; N]".to_string(),
+        Type::Ptr(ptr) if ptr.mutability.is_some() => "*mut T".to_string(),
+        Type::Ptr(_) => "*const T".to_string(),
+        Type::Reference(reference) => type_name(&reference.elem),
+        Type::Paren(paren) => type_name(&paren.elem),
+        Type::Group(group) => type_name(&group.elem),
+        ty => ty.to_token_stream().to_string(),
+    }
+}
+
+/// Render a path without the spaces that `to_string` puts between the tokens.
+pub fn render_path(path: &TypePath) -> String {
+    let mut rendered = String::new();
+    if let Some(qself) = &path.qself {
+        let ty = type_name(&qself.ty);
+        if qself.position == 0 {
+            rendered.push_str(&format!("<{ty}>::"));
+        } else {
+            let trait_name = &path.path.segments[qself.position - 1].ident;
+            rendered.push_str(&format!("<{ty} as {trait_name}>::"));
+        }
+        let rest = path.path.segments.iter().skip(qself.position);
+        rendered.push_str(&rest.map(|seg| seg.ident.to_string()).collect::<Vec<_>>().join("::"));
+    } else {
+        let segments = path.path.segments.iter().map(|seg| seg.ident.to_string());
+        rendered.push_str(&segments.collect::<Vec<_>>().join("::"));
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(source: &str) -> Inventory {
+        let mut inventory = Inventory::default();
+        let (mut contract_traits, mut inheriting) = (HashSet::new(), Vec::new());
+        let mut visitor = Visitor {
+            file: Path::new("lib.rs"),
+            module: vec!["core".to_string()],
+            impls: Vec::new(),
+            inventory: &mut inventory,
+            contract_traits: &mut contract_traits,
+            inheriting: &mut inheriting,
+        };
+        visitor.visit_file(&syn::parse_file(source).unwrap());
+        inventory
+    }
+
+    #[test]
+    fn trusted_functions() {
+        let inventory = scan(
+            r#"
+            impl<Dyn: ?Sized> DynMetadata<Dyn> {
+                #[safety::trusted(reason = "vtables are not modeled")]
+                #[safety::ensures(|size| *size <= isize::MAX as usize)]
+                pub fn size_of(self) -> usize {}
+            }
+            "#,
+        );
+        let [size_of] = &inventory.functions[..] else { panic!("expected one function") };
+        assert_eq!(size_of.display(), "core::DynMetadata::size_of");
+        assert_eq!(size_of.contract, ["trusted", "ensures"]);
+        assert_eq!(size_of.trusted.as_deref(), Some("vtables are not modeled"));
+    }
+
+    #[test]
+    fn auto_harness_verifies_its_function() {
+        let inventory = scan(
+            r#"
+            #[safety::auto_harness(types = [u64, ()])]
+            #[safety::modifies(x)]
+            #[safety::modifies(y)]
+            pub const fn swap<T>(x: &mut T, y: &mut T) {}
+            "#,
+        );
+        let [swap] = &inventory.functions[..] else { panic!("expected one function") };
+        assert_eq!(swap.contract, ["modifies", "modifies"]);
+        let [harness] = &inventory.harnesses[..] else { panic!("expected one harness") };
+        assert_eq!(harness.name, "__safety_harnesses_swap");
+        assert_eq!(harness.target, "swap");
+    }
+}
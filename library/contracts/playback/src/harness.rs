@@ -0,0 +1,188 @@
+//! Find a harness in the sources of the library, with the module it is declared in.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::{Attribute, Item, ItemFn, ItemUse, Visibility};
+
+/// A `proof_for_contract` or `proof` harness.
+pub struct Harness {
+    /// The path of the module of the harness, starting with the name of the crate.
+    pub module: Vec<String>,
+    /// The file of the harness.
+    pub file: PathBuf,
+    pub item: ItemFn,
+    /// The `use` items of the module of the harness.
+    pub uses: Vec<ItemUse>,
+    /// The other functions of the module of the harness, which it may call to draw its inputs.
+    pub helpers: Vec<ItemFn>,
+}
+
+impl Harness {
+    /// The path of the harness, such as `core::ptr::verify::check_read_u128`.
+    pub fn display(&self) -> String {
+        format!("{}::{}", self.module.join("::"), self.item.sig.ident)
+    }
+}
+
+/// Find the harness called `name` in the given crates of the library. If several harnesses have
+/// this name, `filter` must be a suffix of the path of the one to pick.
+pub fn find(
+    library: &Path,
+    crates: &[&str],
+    name: &str,
+    filter: Option<&str>,
+) -> Result<Harness, String> {
+    let mut found = Vec::new();
+    for krate in crates {
+        let root = library.join(krate).join("src");
+        let mut files = Vec::new();
+        collect_files(&root, &mut files);
+        files.sort();
+        for file in files {
+            let Ok(source) = fs::read_to_string(&file) else { continue };
+            // A file that mentions the harness is parsed, the others are not worth it.
+            if !source.contains(name) {
+                continue;
+            }
+            let ast =
+                syn::parse_file(&source).map_err(|err| format!("{}: {err}", file.display()))?;
+            let mut module = module_path(krate, &root, &file);
+            collect_harnesses(&ast.items, &mut module, &file, name, &mut found);
+        }
+    }
+    if let (Some(filter), true) = (filter, found.len() > 1) {
+        found.retain(|harness| harness.display().ends_with(filter));
+    }
+    match found.len() {
+        0 => Err(format!("no harness `{name}` in {}", crates.join(", "))),
+        1 => Ok(found.pop().unwrap()),
+        _ => {
+            let paths: Vec<_> = found.iter().map(Harness::display).collect();
+            Err(format!(
+                "several harnesses `{name}`, pick one with --harness: {}",
+                paths.join(", ")
+            ))
+        }
+    }
+}
+
+fn collect_harnesses(
+    items: &[Item],
+    module: &mut Vec<String>,
+    file: &Path,
+    name: &str,
+    found: &mut Vec<Harness>,
+) {
+    for item in items {
+        match item {
+            Item::Fn(item) if item.sig.ident == name && item.attrs.iter().any(is_harness) => {
+                let uses = items.iter().filter_map(|item| match item {
+                    Item::Use(item) => Some(item.clone()),
+                    _ => None,
+                });
+                let helpers = items.iter().filter_map(|other| match other {
+                    Item::Fn(other) if other.sig.ident != name => Some(other.clone()),
+                    _ => None,
+                });
+                found.push(Harness {
+                    module: module.clone(),
+                    file: file.to_path_buf(),
+                    item: item.clone(),
+                    uses: uses.collect(),
+                    helpers: helpers.collect(),
+                });
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    module.push(item.ident.to_string());
+                    collect_harnesses(items, module, file, name, found);
+                    module.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The names of the items that the module at `path` declares without `pub`, or an error if the
+/// module or one of its ancestors is not public. `path` starts with the name of the crate.
+///
+/// A module that cannot be found, such as one with a `#[path]`, is assumed to declare none.
+pub fn private_items(library: &Path, path: &[String]) -> Result<HashSet<String>, String> {
+    let root = library.join(&path[0]).join("src");
+    let Some(mut items) = parse(&root.join("lib.rs")) else { return Ok(HashSet::new()) };
+    for depth in 1..path.len() {
+        let declaration = items.iter().find_map(|item| match item {
+            Item::Mod(item) if item.ident == path[depth] => Some(item),
+            _ => None,
+        });
+        let Some(declaration) = declaration else { return Ok(HashSet::new()) };
+        if !matches!(declaration.vis, Visibility::Public(_)) {
+            return Err(format!("`{}` is not public", path[..=depth].join("::")));
+        }
+        let next = match &declaration.content {
+            Some((_, content)) => Some(content.clone()),
+            None => {
+                let file = root.join(path[1..=depth].join("/"));
+                parse(&file.with_extension("rs")).or_else(|| parse(&file.join("mod.rs")))
+            }
+        };
+        let Some(next) = next else { return Ok(HashSet::new()) };
+        items = next;
+    }
+    Ok(items.iter().filter_map(private_name).collect())
+}
+
+/// The name of an item that is declared without `pub`.
+fn private_name(item: &Item) -> Option<String> {
+    let (ident, vis) = match item {
+        Item::Fn(item) => (&item.sig.ident, &item.vis),
+        Item::Struct(item) => (&item.ident, &item.vis),
+        Item::Enum(item) => (&item.ident, &item.vis),
+        Item::Union(item) => (&item.ident, &item.vis),
+        Item::Trait(item) => (&item.ident, &item.vis),
+        Item::Type(item) => (&item.ident, &item.vis),
+        Item::Const(item) => (&item.ident, &item.vis),
+        Item::Static(item) => (&item.ident, &item.vis),
+        Item::Mod(item) => (&item.ident, &item.vis),
+        _ => return None,
+    };
+    (!matches!(vis, Visibility::Public(_))).then(|| ident.to_string())
+}
+
+fn parse(file: &Path) -> Option<Vec<Item>> {
+    let source = fs::read_to_string(file).ok()?;
+    Some(syn::parse_file(&source).ok()?.items)
+}
+
+fn is_harness(attr: &Attribute) -> bool {
+    let path = attr.path();
+    path.segments.len() == 2
+        && path.segments[0].ident == "kani"
+        && (path.segments[1].ident == "proof_for_contract" || path.segments[1].ident == "proof")
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// The path of the module of a file, assuming the usual layout of modules.
+fn module_path(krate: &str, root: &Path, file: &Path) -> Vec<String> {
+    let mut module = vec![krate.to_string()];
+    let relative = file.strip_prefix(root).unwrap_or(file).with_extension("");
+    module.extend(relative.iter().map(|part| part.to_string_lossy().into_owned()));
+    if matches!(module.last().map(String::as_str), Some("mod" | "lib")) {
+        module.pop();
+    }
+    module
+}
@@ -0,0 +1,97 @@
+//! Turn the counterexamples of failing harnesses into regression tests.
+//!
+//! The input is the output of `cargo kani --concrete-playback=print`, or a file in which Kani
+//! inserted its tests with `--concrete-playback=inplace`. For each concrete playback test, the
+//! harness it replays is found in the sources of `core` and `alloc`, and its body is copied into a
+//! `#[test]` where each value drawn with `kani::any()` or `kani::any_where()` is replaced by the
+//! concrete one, and each `kani::assume` by an assertion. The test then runs with a plain
+//! `cargo test`, which checks the contracts with the runtime backend, so that the counterexample
+//! stays covered once it is fixed.
+//!
+//! ```text
+//! cargo run -- [--library <dir>] [--harness <path>] [--print] [<playback>]
+//! ```
+//!
+//! The playback is read from the standard input if no file is given. The test is appended to the
+//! `tests.rs` file of the module of the harness, or for `core`, which has no unit tests, to the
+//! file of its top-level module in `core/tests`, unless the file already has it. `--print` prints
+//! the tests instead. `--harness` picks a harness by a suffix of its path, such as
+//! `ptr::verify::check_read_u128`, if several have the same name.
+//!
+//! The harness must draw its values in the order of its source, with a type that is written in the
+//! call or in the `let` that binds it, and only primitive types, arrays and tuples of them can be
+//! replayed. A harness that only calls a generic function of its module, such as
+//! `check_swap::<u8>()`, is replaced by the body of that function. The integration tests of `core`
+//! only reach its public items, so a harness of `core` that uses an item that is not public, such
+//! as a `pub(crate)` function or an item of a `pub(crate)` module, is rejected with an error.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod harness;
+mod playback;
+mod replay;
+
+const CRATES: &[&str] = &["core", "alloc"];
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut library = manifest_dir.join("../..");
+    let mut filter = None;
+    let mut print = false;
+    let mut input = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--library" => library = PathBuf::from(value()?),
+            "--harness" => filter = Some(value()?),
+            "--print" => print = true,
+            _ if arg.starts_with("--") => return Err(format!("unknown argument `{arg}`")),
+            _ => input = Some(PathBuf::from(arg)),
+        }
+    }
+    let library = library.canonicalize().map_err(|err| format!("{}: {err}", library.display()))?;
+
+    let output = match &input {
+        Some(path) => {
+            fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?
+        }
+        None => {
+            let mut output = String::new();
+            io::stdin().read_to_string(&mut output).map_err(|err| err.to_string())?;
+            output
+        }
+    };
+    for playback in playback::parse(&output)? {
+        let harness = harness::find(&library, CRATES, &playback.harness, filter.as_deref())?;
+        let test = replay::regression_test(&library, &playback, &harness)?;
+        if print {
+            println!("{}", test.code);
+            continue;
+        }
+        let path = &test.file;
+        let source =
+            fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        if source.contains(&format!("fn {}(", test.name)) {
+            println!("{}: already has {}", path.display(), test.name);
+            continue;
+        }
+        let source = format!("{}\n\n{}", source.trim_end(), test.code);
+        fs::write(path, source).map_err(|err| format!("{}: {err}", path.display()))?;
+        println!("{}: added {} for {}", path.display(), test.name, harness.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! Parse the unit tests that Kani prints with `--concrete-playback=print`, or inserts in the
+//! sources with `--concrete-playback=inplace`.
+
+use syn::punctuated::Punctuated;
+use syn::{Expr, ExprCall, ExprMacro, ItemFn, Lit, Local, Stmt, Token};
+
+/// The counterexample of a failing harness.
+pub struct Playback {
+    /// The name of the test generated by Kani, such as
+    /// `kani_concrete_playback_check_read_u128_14615086421508420155`.
+    pub name: String,
+    /// The name of the harness.
+    pub harness: String,
+    /// The bytes of each value drawn by the harness, in the order it draws them.
+    pub values: Vec<Vec<u8>>,
+}
+
+const PREFIX: &str = "fn kani_concrete_playback_";
+
+/// Parse every concrete playback test of the output of Kani.
+pub fn parse(output: &str) -> Result<Vec<Playback>, String> {
+    let mut playbacks = Vec::new();
+    let mut rest = output;
+    while let Some(start) = rest.find(PREFIX) {
+        let end = item_end(&rest[start..])
+            .ok_or_else(|| format!("unterminated test `{}`", first_line(&rest[start..])))?;
+        let text = &rest[start..start + end];
+        let item: ItemFn = syn::parse_str(text)
+            .map_err(|err| format!("cannot parse test `{}`: {err}", first_line(text)))?;
+        playbacks.push(Playback::from_test(&item)?);
+        rest = &rest[start + end..];
+    }
+    if playbacks.is_empty() {
+        return Err("no concrete playback test in the input".to_string());
+    }
+    Ok(playbacks)
+}
+
+impl Playback {
+    fn from_test(item: &ItemFn) -> Result<Playback, String> {
+        let name = item.sig.ident.to_string();
+        let mut values = None;
+        let mut harness = None;
+        for stmt in &item.block.stmts {
+            match stmt {
+                Stmt::Local(Local { init: Some(init), .. }) => {
+                    if let Expr::Macro(vec) = &*init.expr {
+                        values = Some(bytes(vec).map_err(|err| format!("{name}: {err}"))?);
+                    }
+                }
+                Stmt::Expr(Expr::Call(call), _) if is_playback_run(call) => {
+                    if let Some(Expr::Path(path)) = call.args.last() {
+                        harness = path.path.segments.last().map(|seg| seg.ident.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        let values = values.ok_or_else(|| format!("{name}: no concrete values"))?;
+        let harness = harness.ok_or_else(|| format!("{name}: no `concrete_playback_run` call"))?;
+        Ok(Playback { name, harness, values })
+    }
+}
+
+fn is_playback_run(call: &ExprCall) -> bool {
+    let Expr::Path(func) = &*call.func else { return false };
+    func.path.segments.last().is_some_and(|seg| seg.ident == "concrete_playback_run")
+}
+
+/// The bytes of `vec![vec![0, 128], ...]`.
+fn bytes(vec: &ExprMacro) -> Result<Vec<Vec<u8>>, String> {
+    let parse = Punctuated::<Expr, Token![,]>::parse_terminated;
+    let values = vec.mac.parse_body_with(parse).map_err(|err| err.to_string())?;
+    values
+        .iter()
+        .map(|value| {
+            let Expr::Macro(value) = value else { return Err("expected `vec![...]`".to_string()) };
+            let bytes = value.mac.parse_body_with(parse).map_err(|err| err.to_string())?;
+            bytes
+                .iter()
+                .map(|byte| match byte {
+                    Expr::Lit(lit) => match &lit.lit {
+                        Lit::Int(int) => int.base10_parse::<u8>().map_err(|err| err.to_string()),
+                        _ => Err("expected a byte".to_string()),
+                    },
+                    _ => Err("expected a byte".to_string()),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The length of the item that starts `text`, up to its closing brace.
+fn item_end(text: &str) -> Option<usize> {
+    let open = text.find('{')?;
+    let mut depth = 0;
+    for (index, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or(text)
+}
@@ -0,0 +1,588 @@
+//! Rewrite a harness into a unit test that replays a counterexample: each value that the harness
+//! draws is replaced by the literal that Kani found, so that the test runs without Kani and checks
+//! the contracts with the runtime backend.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::vec::IntoIter;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, Block, Expr, ExprCall, GenericArgument, Ident, Item, ItemFn, Local, Pat,
+    PathArguments, Stmt, Type, UseName, UseRename, UseTree,
+};
+
+use crate::harness::{private_items, Harness};
+use crate::playback::Playback;
+
+/// A regression test, and the file it belongs to.
+pub struct Test {
+    pub file: PathBuf,
+    pub name: String,
+    pub code: String,
+}
+
+/// Build the regression test of the counterexample of `harness`.
+///
+/// The test of a harness of `core` goes to the integration tests of `core`, in the file of the
+/// top-level module of the harness, since `core` has no unit tests. The test of a harness of
+/// another crate goes to the `tests.rs` file of the module of the harness.
+///
+/// The integration tests of `core` only reach its public items, so a harness of `core` whose test
+/// would use an item that is not public, or that is in a module that is not, is rejected.
+pub fn regression_test(
+    library: &Path,
+    playback: &Playback,
+    harness: &Harness,
+) -> Result<Test, String> {
+    let krate = harness.module[0].as_str();
+    let (file, root) = if krate == "core" {
+        let Some(top) = harness.module.get(1) else {
+            return Err(format!("{} is not in a module of `core`", harness.display()));
+        };
+        let tests = library.join("core/tests");
+        let file = tests.join(format!("{top}.rs"));
+        (if file.exists() { file } else { tests.join(top).join("mod.rs") }, "core")
+    } else {
+        let dir = harness.file.parent().unwrap_or(Path::new(""));
+        let stem = harness.file.file_stem().unwrap_or_default();
+        let file = if stem == "mod" || stem == "lib" {
+            dir.join("tests.rs")
+        } else {
+            dir.join(stem).join("tests.rs")
+        };
+        (file, "crate")
+    };
+    if !file.exists() {
+        return Err(format!("{}: no test file for {}", file.display(), harness.display()));
+    }
+
+    let mut block = inline_helper(&harness.item.block, &harness.helpers)
+        .unwrap_or_else(|| (*harness.item.block).clone());
+    let mut replay = Replay { values: playback.values.clone().into_iter(), error: None };
+    replay.visit_block_mut(&mut block);
+    if let Some(error) = replay.error {
+        return Err(format!("{}: {error}", harness.display()));
+    }
+    let left = replay.values.len();
+    if left > 0 {
+        return Err(format!("{}: {left} concrete values are not drawn", harness.display()));
+    }
+    if block.to_token_stream().to_string().contains("kani ::") {
+        return Err(format!(
+            "{}: only `kani::any`, `any_where`, `assume` and `assert` can be replayed",
+            harness.display()
+        ));
+    }
+
+    // The imports of the module of the harness that the body uses, other than `kani`.
+    let mut idents = HashSet::new();
+    collect_idents(block.to_token_stream(), &mut idents);
+    let mut uses = Vec::new();
+    for item in &harness.uses {
+        let mut leaves = Vec::new();
+        use_leaves(&item.tree, &mut leaves);
+        let used = leaves.iter().any(|leaf| match leaf {
+            Some(ident) => idents.contains(ident),
+            None => true,
+        });
+        if !used || item.tree.to_token_stream().to_string().contains("kani") {
+            continue;
+        }
+        let mut item = item.clone();
+        item.tree = absolute(&item.tree, &harness.module, root)?;
+        if root == "core" {
+            reachable(library, &item.tree, &mut Vec::new(), &idents).map_err(|err| {
+                format!("{}: {err}, and the tests of `core` cannot use it", harness.display())
+            })?;
+        }
+        uses.push(item);
+    }
+    let name = Ident::new(&playback.name, Span::call_site());
+    let stmts = &block.stmts;
+    let test: ItemFn = parse_quote!(
+        #[test]
+        fn #name() {
+            #(#uses)*
+            #(#stmts)*
+        }
+    );
+    let file_ast = syn::File { shebang: None, attrs: Vec::new(), items: vec![Item::Fn(test)] };
+    let code = format!(
+        "// Replays the counterexample of `{}`.\n{}",
+        harness.display(),
+        prettyplease::unparse(&file_ast)
+    );
+    Ok(Test { file, name: playback.name.clone(), code })
+}
+
+/// The body of the helper that a harness only calls, such as `check_swap::<u8>()`, with its type
+/// parameters replaced by the arguments of the call.
+fn inline_helper(block: &Block, helpers: &[ItemFn]) -> Option<Block> {
+    let [Stmt::Expr(Expr::Call(call), _)] = block.stmts.as_slice() else { return None };
+    let Expr::Path(func) = &*call.func else { return None };
+    let [segment] = func.path.segments.iter().collect::<Vec<_>>()[..] else { return None };
+    let helper = helpers.iter().find(|helper| helper.sig.ident == segment.ident)?;
+    if !call.args.is_empty() || !helper.sig.inputs.is_empty() {
+        return None;
+    }
+    let args: Vec<Type> = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let params = helper.sig.generics.type_params().map(|param| param.ident.clone());
+    let mut subst = Subst(params.zip(args).collect());
+    let mut block = (*helper.block).clone();
+    subst.visit_block_mut(&mut block);
+    Some(block)
+}
+
+/// Replace type parameters by types.
+struct Subst(HashMap<Ident, Type>);
+
+impl VisitMut for Subst {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            if let Some(ident) = path.path.get_ident() {
+                if let Some(arg) = self.0.get(ident) {
+                    *ty = arg.clone();
+                    return;
+                }
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+/// Replace the values drawn by a harness with the concrete ones, in the order of the source.
+struct Replay {
+    values: IntoIter<Vec<u8>>,
+    error: Option<String>,
+}
+
+impl Replay {
+    fn fail(&mut self, error: String) {
+        self.error.get_or_insert(error);
+    }
+
+    /// The literal of the next value of type `ty`.
+    fn literal(&mut self, ty: &Type) -> Result<Expr, String> {
+        match ty {
+            Type::Path(path) if path.path.get_ident().is_some() => {
+                let name = path.path.get_ident().unwrap().to_string();
+                let size = size_of(&name).ok_or_else(|| unsupported(ty))?;
+                let bytes = self.next(&name, size)?;
+                primitive(&name, &bytes).ok_or_else(|| unsupported(ty))
+            }
+            Type::Array(array) => {
+                let Expr::Lit(len) = &array.len else { return Err(unsupported(ty)) };
+                let syn::Lit::Int(len) = &len.lit else { return Err(unsupported(ty)) };
+                let len: usize = len.base10_parse().map_err(|err| err.to_string())?;
+                // Kani draws an array of integers or floats as a single value.
+                let elem = array.elem.to_token_stream().to_string();
+                let size = size_of(&elem).filter(|_| elem != "bool" && elem != "char");
+                let whole = size.filter(|size| {
+                    self.values.as_slice().first().map(Vec::len) == Some(size * len)
+                });
+                let elems: Vec<Expr> = match whole {
+                    Some(size) => {
+                        let bytes = self.values.next().unwrap();
+                        bytes.chunks(size).map(|bytes| primitive(&elem, bytes).unwrap()).collect()
+                    }
+                    None => {
+                        (0..len).map(|_| self.literal(&array.elem)).collect::<Result<_, _>>()?
+                    }
+                };
+                Ok(parse_quote!([#(#elems),*]))
+            }
+            Type::Tuple(tuple) => {
+                let elems: Vec<Expr> =
+                    tuple.elems.iter().map(|elem| self.literal(elem)).collect::<Result<_, _>>()?;
+                Ok(parse_quote!((#(#elems,)*)))
+            }
+            Type::Paren(paren) => self.literal(&paren.elem),
+            _ => Err(unsupported(ty)),
+        }
+    }
+
+    fn next(&mut self, name: &str, size: usize) -> Result<Vec<u8>, String> {
+        let bytes = self.values.next().ok_or("the harness draws more values than Kani found")?;
+        // `usize` and `isize` have the size of a pointer of the target that Kani verified.
+        let pointer =
+            (name == "usize" || name == "isize") && (bytes.len() == 4 || bytes.len() == 8);
+        if bytes.len() != size && !pointer {
+            return Err(format!("a `{name}` has {size} bytes, not {}", bytes.len()));
+        }
+        Ok(bytes)
+    }
+
+    /// The literal that replaces a call to `kani::any` or `kani::any_where`, whose type is `hint`
+    /// if it is not given by the call.
+    fn replace_any(&mut self, call: &ExprCall, hint: Option<&Type>) -> Option<Expr> {
+        let name = kani_call(call)?;
+        if name != "any" && name != "any_where" {
+            return None;
+        }
+        let ty = turbofish(call)
+            .or_else(|| closure_param(call))
+            .or_else(|| hint.cloned())
+            .ok_or_else(|| "annotate the type of each `kani::any()`".to_string());
+        match ty.and_then(|ty| self.literal(&ty)) {
+            Ok(literal) => Some(literal),
+            Err(error) => {
+                self.fail(error);
+                None
+            }
+        }
+    }
+}
+
+impl VisitMut for Replay {
+    fn visit_local_mut(&mut self, local: &mut Local) {
+        if let (Pat::Type(pat), Some(init)) = (&local.pat, &mut local.init) {
+            if let Expr::Call(call) = &*init.expr {
+                if let Some(literal) = self.replace_any(call, Some(&pat.ty)) {
+                    *init.expr = literal;
+                    return;
+                }
+            }
+        }
+        visit_mut::visit_local_mut(self, local);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Call(call) = expr {
+            if let Some(literal) = self.replace_any(call, None) {
+                *expr = literal;
+                return;
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+        let Expr::Call(call) = expr else { return };
+        let args: Vec<_> = call.args.iter().collect();
+        // The concrete values satisfy the assumptions, which the test checks all the same.
+        *expr = match (kani_call(call).as_deref(), args.as_slice()) {
+            (Some("assume"), [cond]) => parse_quote!(assert!(#cond)),
+            (Some("assert"), [cond, msg]) => parse_quote!(assert!(#cond, "{}", #msg)),
+            _ => return,
+        };
+    }
+}
+
+/// The name of the function of `kani` that `call` calls, if any.
+fn kani_call(call: &ExprCall) -> Option<String> {
+    let Expr::Path(func) = &*call.func else { return None };
+    let segments: Vec<_> = func.path.segments.iter().collect();
+    match segments[..] {
+        [kani, name] if kani.ident == "kani" => Some(name.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// The type argument of `kani::any::<T>()`.
+fn turbofish(call: &ExprCall) -> Option<Type> {
+    let Expr::Path(func) = &*call.func else { return None };
+    let PathArguments::AngleBracketed(args) = &func.path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    })
+}
+
+/// The type `T` of the parameter of the closure of `kani::any_where(|value: &T| ...)`.
+fn closure_param(call: &ExprCall) -> Option<Type> {
+    let Some(Expr::Closure(closure)) = call.args.first() else { return None };
+    let Some(Pat::Type(pat)) = closure.inputs.first() else { return None };
+    match &*pat.ty {
+        Type::Reference(reference) => Some((*reference.elem).clone()),
+        _ => None,
+    }
+}
+
+fn size_of(name: &str) -> Option<usize> {
+    Some(match name {
+        "u8" | "i8" | "bool" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" | "char" => 4,
+        "u64" | "i64" | "f64" | "usize" | "isize" => 8,
+        "u128" | "i128" => 16,
+        _ => return None,
+    })
+}
+
+/// The literal of a value of a primitive type from its little-endian bytes.
+fn primitive(name: &str, bytes: &[u8]) -> Option<Expr> {
+    let mut buf = [0; 16];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    let bits = u128::from_le_bytes(buf);
+    // Sign-extend the value of a signed integer.
+    let shift = 128 - 8 * bytes.len() as u32;
+    let signed = ((bits << shift) as i128) >> shift;
+    let literal = match name {
+        "bool" => (bits != 0).to_string(),
+        "char" => format!("{:?}", char::from_u32(bits as u32)?),
+        "f32" => format!("f32::from_bits({bits:#x})"),
+        "f64" => format!("f64::from_bits({bits:#x})"),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => format!("{signed}{name}"),
+        _ => format!("{bits}{name}"),
+    };
+    syn::parse_str(&literal).ok()
+}
+
+fn unsupported(ty: &Type) -> String {
+    format!("cannot replay a value of type `{}`", ty.to_token_stream())
+}
+
+/// Make a `use` tree that starts with `super`, `self` or `crate` in `module` absolute, starting
+/// with `root` instead of the name of the crate.
+fn absolute(tree: &UseTree, module: &[String], root: &str) -> Result<UseTree, String> {
+    let mut prefix: Option<Vec<String>> = None;
+    let mut rest = tree;
+    while let UseTree::Path(path) = rest {
+        match path.ident.to_string().as_str() {
+            "super" => {
+                prefix.get_or_insert_with(|| module.to_vec()).pop();
+            }
+            "self" if prefix.is_none() => prefix = Some(module.to_vec()),
+            "crate" if prefix.is_none() => prefix = Some(vec![module[0].clone()]),
+            _ => break,
+        }
+        rest = &path.tree;
+    }
+    let Some(mut prefix) = prefix else { return Ok(tree.clone()) };
+    if prefix.is_empty() {
+        return Err(format!("`use {}` leaves the crate", tree.to_token_stream()));
+    }
+    prefix[0] = root.to_string();
+    let path = format!("{}::{}", prefix.join("::"), rest.to_token_stream());
+    syn::parse_str(&path).map_err(|err| format!("`use {path}`: {err}"))
+}
+
+/// Check that the items of `core` that a `use` tree at `prefix` imports are public, as are their
+/// modules. Of the items that a glob imports, only the ones in `idents` are checked.
+fn reachable(
+    library: &Path,
+    tree: &UseTree,
+    prefix: &mut Vec<String>,
+    idents: &HashSet<Ident>,
+) -> Result<(), String> {
+    let private = |prefix: &[String]| match prefix.first() {
+        Some(krate) if krate == "core" => private_items(library, prefix),
+        _ => Ok(HashSet::new()),
+    };
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            let result = reachable(library, &path.tree, prefix, idents);
+            prefix.pop();
+            result
+        }
+        UseTree::Name(UseName { ident }) | UseTree::Rename(UseRename { ident, .. }) => {
+            if ident != "self" && private(prefix)?.contains(&ident.to_string()) {
+                return Err(format!("`{}::{ident}` is not public", prefix.join("::")));
+            }
+            Ok(())
+        }
+        UseTree::Glob(_) => {
+            let private = private(prefix)?;
+            let mut used: Vec<_> = idents
+                .iter()
+                .map(Ident::to_string)
+                .filter(|ident| private.contains(ident))
+                .collect();
+            used.sort();
+            match used.first() {
+                Some(ident) => Err(format!("`{}::{ident}` is not public", prefix.join("::"))),
+                None => Ok(()),
+            }
+        }
+        UseTree::Group(group) => {
+            group.items.iter().try_for_each(|tree| reachable(library, tree, prefix, idents))
+        }
+    }
+}
+
+/// The names that a `use` tree imports, where `None` stands for a glob.
+fn use_leaves(tree: &UseTree, leaves: &mut Vec<Option<Ident>>) {
+    match tree {
+        UseTree::Path(path) => use_leaves(&path.tree, leaves),
+        UseTree::Name(name) => leaves.push(Some(name.ident.clone())),
+        UseTree::Rename(rename) => leaves.push(Some(rename.rename.clone())),
+        UseTree::Glob(_) => leaves.push(None),
+        UseTree::Group(group) => group.items.iter().for_each(|tree| use_leaves(tree, leaves)),
+    }
+}
+
+/// The identifiers of `tokens`, including the ones in the arguments of macros.
+fn collect_idents(tokens: TokenStream, idents: &mut HashSet<Ident>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                idents.insert(ident);
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::harness;
+
+    /// A library with harnesses of `core`, modeled on the ones of `typed_swap`, `transmute_unchecked`
+    /// and `Alignment::new`, in a new directory named after the test.
+    fn library(test: &str) -> PathBuf {
+        let library = std::env::temp_dir().join(format!("playback-{}-{test}", std::process::id()));
+        let _ = fs::remove_dir_all(&library);
+        let files = [
+            ("core/src/lib.rs", "pub mod intrinsics;\npub mod mem;\npub mod ptr;\n"),
+            (
+                "core/src/intrinsics.rs",
+                r#"
+                pub const unsafe fn typed_swap<T>(x: *mut T, y: *mut T) {}
+
+                #[cfg(kani)]
+                pub(crate) mod verify {
+                    use core::{cmp, fmt};
+                    use super::*;
+                    use crate::kani;
+
+                    #[kani::proof_for_contract(typed_swap)]
+                    pub fn check_typed_swap_u8() {
+                        check_swap::<u8>()
+                    }
+
+                    pub fn check_swap<T: kani::Arbitrary + Copy + cmp::PartialEq + fmt::Debug>() {
+                        let mut x = kani::any::<T>();
+                        let old_x = x;
+                        let mut y = kani::any::<T>();
+                        let old_y = y;
+
+                        unsafe { typed_swap(&mut x, &mut y) };
+                        assert_eq!(y, old_x);
+                        assert_eq!(x, old_y);
+                    }
+                }
+                "#,
+            ),
+            (
+                "core/src/mem.rs",
+                r#"
+                pub(crate) const unsafe fn transmute_unchecked<Src, Dst>(src: Src) -> Dst {}
+
+                #[cfg(kani)]
+                pub(crate) mod verify {
+                    use super::*;
+                    use crate::kani;
+
+                    #[kani::proof_for_contract(transmute_unchecked)]
+                    pub fn check_transmute_unchecked_char() {
+                        let src: u32 = kani::any();
+                        let _: char = unsafe { transmute_unchecked(src) };
+                    }
+                }
+                "#,
+            ),
+            ("core/src/ptr/mod.rs", "pub(crate) mod alignment;\n"),
+            (
+                "core/src/ptr/alignment.rs",
+                r#"
+                pub struct Alignment(usize);
+
+                #[cfg(kani)]
+                pub(crate) mod verify {
+                    use super::*;
+                    use crate::kani;
+
+                    #[kani::proof_for_contract(Alignment::new)]
+                    pub fn check_new() {
+                        let _ = Alignment::new(kani::any::<usize>());
+                    }
+                }
+                "#,
+            ),
+            ("core/tests/intrinsics.rs", ""),
+            ("core/tests/mem.rs", ""),
+            ("core/tests/ptr.rs", ""),
+        ];
+        for (file, source) in files {
+            let file = library.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, source).unwrap();
+        }
+        library
+    }
+
+    fn replay(library: &Path, harness: &str, values: Vec<Vec<u8>>) -> Result<Test, String> {
+        let found = harness::find(library, &["core"], harness, None)?;
+        let name = format!("kani_concrete_playback_{harness}_1");
+        let playback = Playback { name, harness: harness.to_string(), values };
+        regression_test(library, &playback, &found)
+    }
+
+    #[test]
+    fn typed_swap() {
+        let library = library("typed_swap");
+        let test = replay(&library, "check_typed_swap_u8", vec![vec![1], vec![2]]).unwrap();
+        assert_eq!(test.file, library.join("core/tests/intrinsics.rs"));
+        assert_eq!(test.name, "kani_concrete_playback_check_typed_swap_u8_1");
+        let expected = r#"// Replays the counterexample of `core::intrinsics::verify::check_typed_swap_u8`.
+#[test]
+fn kani_concrete_playback_check_typed_swap_u8_1() {
+    use core::intrinsics::*;
+    let mut x = 1u8;
+    let old_x = x;
+    let mut y = 2u8;
+    let old_y = y;
+    unsafe { typed_swap(&mut x, &mut y) };
+    assert_eq!(y, old_x);
+    assert_eq!(x, old_y);
+}
+"#;
+        assert_eq!(test.code, expected);
+    }
+
+    #[test]
+    fn values_must_match_the_harness() {
+        let library = library("values");
+        let left = replay(&library, "check_typed_swap_u8", vec![vec![1], vec![2], vec![3]]);
+        assert!(left.err().unwrap().ends_with("1 concrete values are not drawn"));
+        let missing = replay(&library, "check_typed_swap_u8", vec![vec![1]]);
+        assert!(missing.err().unwrap().ends_with("the harness draws more values than Kani found"));
+        let size = replay(&library, "check_typed_swap_u8", vec![vec![1, 0], vec![2]]);
+        assert!(size.err().unwrap().ends_with("a `u8` has 1 bytes, not 2"));
+    }
+
+    #[test]
+    fn private_items_of_core() {
+        let library = library("private");
+        let item = replay(&library, "check_transmute_unchecked_char", vec![vec![97, 0, 0, 0]]);
+        assert_eq!(
+            item.err().unwrap(),
+            "core::mem::verify::check_transmute_unchecked_char: \
+             `core::mem::transmute_unchecked` is not public, and the tests of `core` cannot use it"
+        );
+        let module = replay(&library, "check_new", vec![vec![8, 0, 0, 0, 0, 0, 0, 0]]);
+        assert_eq!(
+            module.err().unwrap(),
+            "core::ptr::alignment::verify::check_new: \
+             `core::ptr::alignment` is not public, and the tests of `core` cannot use it"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 fn main() {
     // We add the configurations here to be checked.
     println!("cargo:rustc-check-cfg=cfg(kani_host)");
+    println!("cargo:rustc-check-cfg=cfg(safety_random)");
 }
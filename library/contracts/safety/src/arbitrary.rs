@@ -0,0 +1,69 @@
+//! Derive of the `Arbitrary` trait of the stand-in for the `kani` module of `core`, which the
+//! randomized backend uses. With Kani, the derive of Kani is used instead.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields};
+
+/// Generate the `Arbitrary` impl of a type, which draws each field of a struct, or a variant of
+/// an enum and then its fields. The type parameters of the type are required to implement
+/// `Arbitrary` too.
+pub(crate) fn derive(input: DeriveInput) -> TokenStream {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = any_fields(&data.fields);
+            quote!(Self #fields)
+        }
+        Data::Enum(data) => {
+            if data.variants.is_empty() {
+                abort!(input.ident, "`Arbitrary` cannot be derived for enums without variants");
+            }
+            let count = data.variants.len();
+            let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+                let ident = &variant.ident;
+                let fields = any_fields(&variant.fields);
+                if index + 1 == count {
+                    quote!(_ => Self::#ident #fields)
+                } else {
+                    quote!(#index => Self::#ident #fields)
+                }
+            });
+            quote!(
+                match <usize as ::core::kani::Arbitrary>::any() % #count {
+                    #(#arms,)*
+                }
+            )
+        }
+        Data::Union(_) => abort!(input.ident, "`Arbitrary` cannot be derived for unions"),
+    };
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::core::kani::Arbitrary));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics ::core::kani::Arbitrary for #ident #ty_generics #where_clause {
+            fn any() -> Self {
+                #body
+            }
+        }
+    )
+}
+
+/// The fields of a struct or variant, each drawn with `kani::any()`.
+fn any_fields(fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote!({ #(#idents: ::core::kani::any(),)* })
+        }
+        Fields::Unnamed(fields) => {
+            let anys = fields.unnamed.iter().map(|_| quote!(::core::kani::any()));
+            quote!((#(#anys,)*))
+        }
+        Fields::Unit => quote!(),
+    }
+}
@@ -0,0 +1,675 @@
+//! Tool independent part of the `contracts` attribute, which lets the methods of a trait declare
+//! contracts that the impls of the trait inherit.
+//!
+//! Inheriting the contracts is opt-in: only the impls that are annotated with `contracts` are
+//! instrumented, the others compile as if the trait had no contract. A method that a macro
+//! generates inside of an impl is not seen by the attribute of the impl, so it is annotated with
+//! `#[safety::contracts(Trait)]` itself, which names the trait it inherits its contract from.
+//!
+//! The trait itself is left unchanged apart from its documentation, since adding items to it
+//! would change a public, and possibly stable, API. Instead, its `requires` and `ensures`
+//! attributes are turned into the functions `requires_<name>` and `ensures_<name>` of a hidden
+//! `Contracts` struct, which evaluate the contracts for the implementor that a `PhantomData`
+//! marker gives them. The struct is declared in the module `__safety_contracts_<Trait>` next to
+//! the trait, which is only visible in the crate, so that no public namespace gets a new name: an
+//! impl finds it next to the path through which it names the trait. An impl in another crate
+//! names the struct with `#[safety::contracts(from = path)]` instead, where `path` is a
+//! re-export of the struct by a module that the crate can reach, such as
+//! `core::ub_checks::contracts`. Every method gets both functions, returning `true` when it has
+//! no contract, so that impls can check their methods without knowing which ones have a
+//! contract. On an impl, each method is then instrumented by the tool to call them with its own
+//! arguments.
+
+use proc_macro2::{Delimiter, Group, Spacing, Span, TokenStream, TokenTree};
+use proc_macro_error::{abort, abort_call_site};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{
+    parse_quote, Attribute, Block, Expr, FnArg, GenericArgument, GenericParam, Generics, Ident,
+    ImplItem, Item, ItemFn, ItemImpl, ItemTrait, Pat, Path, PathArguments, ReturnType, Signature,
+    Token, TraitItem, TraitItemFn, Visibility,
+};
+
+use crate::doc::{document, document_text};
+use crate::is_safety_attr;
+use crate::validate;
+
+/// The contract that a method inherits from its trait.
+pub(crate) struct Inherited {
+    /// The hidden struct whose functions evaluate the contract, as in
+    /// `__safety_contracts_GlobalAlloc::Contracts`.
+    owner: TokenStream,
+    /// The `PhantomData` that gives the implementor and the generic arguments of the trait to the
+    /// functions that evaluate the contract.
+    marker: TokenStream,
+    /// The name of the method in the trait, as in `Trait::method`, for error messages.
+    #[cfg_attr(kani_host, allow(dead_code))]
+    pub(crate) name: String,
+    requires: Ident,
+    ensures: Ident,
+    /// The parameters of the method, and whether they are `&mut` references.
+    params: Vec<(Ident, bool)>,
+}
+
+impl Inherited {
+    /// The expression that evaluates the preconditions on the arguments of the method.
+    pub(crate) fn requires(&self) -> TokenStream {
+        let Inherited { owner, marker, requires, .. } = self;
+        let args = self.args(false);
+        quote!(#owner::#requires(#marker, #(#args),*))
+    }
+
+    /// The expression that evaluates the postconditions on `result`, and on the arguments from
+    /// when the method was entered.
+    pub(crate) fn ensures(&self, result: &Ident) -> TokenStream {
+        let Inherited { owner, marker, ensures, .. } = self;
+        let args = self.args(true);
+        quote!(#owner::#ensures(#marker, #(#args,)* #result))
+    }
+
+    /// The arguments to pass to the contract, in the order of the parameters.
+    ///
+    /// The arguments are cloned, since the method needs them after the contract was evaluated, and
+    /// `&mut` references are reborrowed. If `old` is set, the values that are cloned are the ones
+    /// from when the method was entered, which is needed to check a postcondition after the body
+    /// consumed the arguments.
+    fn args(&self, old: bool) -> Vec<TokenStream> {
+        self.params
+            .iter()
+            .map(|(param, is_mut_ref)| match (is_mut_ref, old) {
+                (true, _) => quote!(&mut *#param),
+                (false, false) => quote!(::core::clone::Clone::clone(&#param)),
+                (false, true) => quote!(::core::clone::Clone::clone(&old(#param))),
+            })
+            .collect()
+    }
+}
+
+/// Instruments a method so that it checks the contract it inherits.
+pub(crate) type Instrument = fn(ItemFn, &Inherited) -> ItemFn;
+
+/// The arguments of `contracts`: the trait that a method inherits its contract from, and the
+/// struct that evaluates the contracts of the trait if it cannot be found next to the trait.
+struct Args {
+    trait_path: Option<Path>,
+    from: Option<Path>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Args { trait_path: None, from: None };
+        while !input.is_empty() {
+            if input.peek(Ident) && input.peek2(Token![=]) {
+                let key: Ident = input.parse()?;
+                if key != "from" {
+                    return Err(syn::Error::new(key.span(), "expected `from`"));
+                }
+                input.parse::<Token![=]>()?;
+                args.from = Some(input.parse()?);
+            } else {
+                args.trait_path = Some(input.parse()?);
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Apply `contracts` to a trait, to an impl of a trait or to a method of such an impl. The
+/// provided methods of the trait are instrumented with `provided`, if any, and the methods of
+/// the impl with `instrument`.
+pub(crate) fn contracts(
+    attr: TokenStream,
+    item: TokenStream,
+    provided: Option<Instrument>,
+    instrument: Instrument,
+) -> TokenStream {
+    let args: Args = syn::parse2(attr).unwrap_or_else(|err| abort!(err.span(), err));
+    match syn::parse2(item).unwrap_or_else(|err| abort!(err.span(), err)) {
+        Item::Trait(item_trait) => {
+            if args.trait_path.is_some() || args.from.is_some() {
+                abort_call_site!("`contracts` does not take arguments on a trait");
+            }
+            trait_contracts(item_trait, provided)
+        }
+        Item::Impl(item_impl) => {
+            if let Some(path) = &args.trait_path {
+                abort!(path, "the trait of the impl is the one whose contracts are inherited");
+            }
+            impl_contracts(item_impl, args.from.as_ref(), instrument).into_token_stream()
+        }
+        Item::Fn(fn_item) => {
+            let Some(trait_path) = &args.trait_path else {
+                abort!(fn_item.sig, "`contracts` on a method takes the trait it implements")
+            };
+            method_contracts(fn_item, trait_path, args.from.as_ref(), instrument)
+                .into_token_stream()
+        }
+        item => abort!(
+            item,
+            "`contracts` can only be applied to a trait, to a trait impl or to a method of one"
+        ),
+    }
+}
+
+/// Move the contracts of the methods of a trait to its hidden struct, and instrument the provided
+/// methods with `instrument`, if any.
+fn trait_contracts(mut item_trait: ItemTrait, instrument: Option<Instrument>) -> TokenStream {
+    // The hidden items must never be stable, even if the trait is.
+    let staged = has_stability(&item_trait.attrs)
+        || item_trait
+            .items
+            .iter()
+            .any(|item| matches!(item, TraitItem::Fn(f) if has_stability(&f.attrs)));
+    if let Some(param) = item_trait.generics.const_params().next() {
+        abort!(param, "`contracts` cannot be applied to a trait with const parameters");
+    }
+    let trait_name = item_trait.ident.to_string();
+    let module = contracts_module(&item_trait.ident);
+    let owner = quote!(#module::Contracts);
+    let args: Vec<_> = item_trait.generics.type_params().map(|param| &param.ident).collect();
+    let marker = marker(&args);
+    let mut functions = Vec::new();
+    for item in &mut item_trait.items {
+        let TraitItem::Fn(method) = item else { continue };
+        let mut requires = Vec::new();
+        let mut ensures = Vec::new();
+        let mut clauses = Vec::new();
+        method.attrs.retain(|attr| {
+            if is_safety_attr(attr, "requires") {
+                requires.push(parse_pred(attr, &method.sig, false));
+                clauses.push(("Requires", requires.last().unwrap().to_token_stream()));
+            } else if is_safety_attr(attr, "ensures") {
+                ensures.push(parse_pred(attr, &method.sig, true));
+                clauses.push(("Ensures", ensures.last().unwrap().to_token_stream()));
+            } else {
+                return true;
+            }
+            false
+        });
+        for (clause, tokens) in clauses {
+            document(&mut method.attrs, clause, tokens);
+        }
+        let (ident, generics) = (&item_trait.ident, &item_trait.generics);
+        functions.extend(contract_fns(ident, generics, method, &requires, &ensures, staged));
+
+        if let (Some(instrument), Some(block)) = (instrument, method.default.take()) {
+            let name = format!("{trait_name}::{}", method.sig.ident);
+            let sig = &mut method.sig;
+            let fn_item =
+                instrumented(&method.attrs, sig, block, &owner, &marker, name, instrument);
+            method.attrs = fn_item.attrs;
+            method.default = Some(*fn_item.block);
+        }
+    }
+    document_text(
+        &mut item_trait.attrs,
+        "Inherited by",
+        "the impls that are annotated with `#[safety::contracts]`",
+    );
+
+    let doc = format!("The contracts of the trait `{trait_name}`, see `safety::contracts`.");
+    let cfgs: Vec<_> = item_trait.attrs.iter().filter(|attr| attr.path().is_ident("cfg")).collect();
+    let stability = staged.then(|| quote!(#[unstable(feature = "ub_checks", issue = "none")]));
+    quote!(
+        #item_trait
+
+        #[doc = #doc]
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #(#cfgs)*
+        pub(crate) mod #module {
+            use super::*;
+
+            /// Evaluates the contracts of the trait.
+            #stability
+            pub struct Contracts;
+
+            impl Contracts {
+                #(#functions)*
+            }
+        }
+    )
+}
+
+/// The module that declares the contracts of the trait `ident`.
+fn contracts_module(ident: &Ident) -> Ident {
+    format_ident!("__safety_contracts_{}", ident)
+}
+
+/// The struct that evaluates the contracts of the trait at `trait_path`, either `from` or the one
+/// next to the trait, and the marker that gives it the generic arguments of the trait.
+fn inherit(trait_path: &Path, from: Option<&Path>) -> (TokenStream, TokenStream) {
+    let mut path = trait_path.clone();
+    let last = path.segments.last_mut().unwrap();
+    let args: Vec<_> = match std::mem::replace(&mut last.arguments, PathArguments::None) {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .into_iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    last.ident = contracts_module(&last.ident);
+    let owner = match from {
+        Some(from) => from.to_token_stream(),
+        None => quote!(#path::Contracts),
+    };
+    (owner, marker(&args))
+}
+
+/// Instrument every method of a trait impl with `instrument`.
+///
+/// The methods that are generated by a macro inside of the impl are not instrumented.
+fn impl_contracts(
+    mut item_impl: ItemImpl,
+    from: Option<&Path>,
+    instrument: Instrument,
+) -> ItemImpl {
+    let Some((_, trait_path, _)) = &item_impl.trait_ else {
+        abort!(item_impl.self_ty, "`contracts` can only be applied to a trait or to a trait impl");
+    };
+    let trait_name = trait_path.segments.last().unwrap().ident.to_string();
+    let (owner, marker) = inherit(trait_path, from);
+    for item in &mut item_impl.items {
+        let ImplItem::Fn(method) = item else { continue };
+        let name = format!("{trait_name}::{}", method.sig.ident);
+        let block = Block { brace_token: method.block.brace_token, stmts: Vec::new() };
+        let block = std::mem::replace(&mut method.block, block);
+        let sig = &mut method.sig;
+        let fn_item = instrumented(&method.attrs, sig, block, &owner, &marker, name, instrument);
+        method.attrs = fn_item.attrs;
+        method.block = *fn_item.block;
+    }
+    item_impl
+}
+
+/// Instrument a method of an impl of the trait at `trait_path` with `instrument`.
+fn method_contracts(
+    fn_item: ItemFn,
+    trait_path: &Path,
+    from: Option<&Path>,
+    instrument: Instrument,
+) -> ItemFn {
+    let ItemFn { attrs, vis, mut sig, block } = fn_item;
+    let trait_name = trait_path.segments.last().unwrap().ident.to_string();
+    let name = format!("{trait_name}::{}", sig.ident);
+    let (owner, marker) = inherit(trait_path, from);
+    let fn_item = instrumented(&attrs, &mut sig, *block, &owner, &marker, name, instrument);
+    ItemFn { vis, ..fn_item }
+}
+
+/// The `PhantomData` value through which the contract learns the implementor and the generic
+/// arguments of the trait. Raw pointers keep it `Sized` for unsized implementors.
+fn marker(args: &[impl ToTokens]) -> TokenStream {
+    quote!(::core::marker::PhantomData::<(*const Self, #(*const #args,)*)>)
+}
+
+fn instrumented(
+    attrs: &[Attribute],
+    sig: &mut Signature,
+    mut block: Block,
+    owner: &TokenStream,
+    marker: &TokenStream,
+    name: String,
+    instrument: Instrument,
+) -> ItemFn {
+    let mut params = Vec::new();
+    let mut destructure = Vec::new();
+    for (idx, input) in sig.inputs.iter_mut().enumerate() {
+        let (arg, is_mut_ref) = match input {
+            FnArg::Receiver(receiver) => {
+                let is_mut_ref = receiver.reference.is_some() && receiver.mutability.is_some();
+                (Ident::new("self", receiver.self_token.span), is_mut_ref)
+            }
+            FnArg::Typed(pat_type) => {
+                let is_mut_ref =
+                    matches!(&*pat_type.ty, syn::Type::Reference(r) if r.mutability.is_some());
+                let arg = match &*pat_type.pat {
+                    Pat::Ident(pat) if pat.subpat.is_none() => pat.ident.clone(),
+                    // Give a name to the arguments that are matched against a pattern, so that
+                    // they can be passed to the contract.
+                    pat => {
+                        let arg = Ident::new(&format!("arg_{idx}"), Span::mixed_site());
+                        destructure.push(quote!(let #pat = #arg;));
+                        *pat_type.pat = parse_quote!(#arg);
+                        arg
+                    }
+                };
+                (arg, is_mut_ref)
+            }
+        };
+        params.push((arg, is_mut_ref));
+    }
+    if !destructure.is_empty() {
+        let stmts = &block.stmts;
+        block = parse_quote!({
+            #(#destructure)*
+            #(#stmts)*
+        });
+    }
+    let ident = &sig.ident;
+    let inherited = Inherited {
+        owner: owner.clone(),
+        marker: marker.clone(),
+        name,
+        requires: format_ident!("requires_{}", ident),
+        ensures: format_ident!("ensures_{}", ident),
+        params,
+    };
+    let fn_item = ItemFn {
+        attrs: attrs.to_vec(),
+        vis: Visibility::Inherited,
+        sig: sig.clone(),
+        block: Box::new(block),
+    };
+    instrument(fn_item, &inherited)
+}
+
+/// Generate the functions of the hidden struct that evaluate the contract of `method`.
+///
+/// They take the parameters of the method, after the marker that gives them the implementor as
+/// `__Self` and the generic arguments of the trait, and `self` is renamed to `__self`.
+fn contract_fns(
+    trait_ident: &Ident,
+    trait_generics: &Generics,
+    method: &TraitItemFn,
+    requires: &[Expr],
+    ensures: &[Expr],
+    staged: bool,
+) -> [TokenStream; 2] {
+    let ident = &method.sig.ident;
+    let trait_args: Vec<_> = trait_generics.type_params().map(|param| &param.ident).collect();
+    let marker_ty = quote!(::core::marker::PhantomData<(*const __Self, #(*const #trait_args,)*)>);
+
+    // The generic parameters of the trait and of the method, with the lifetimes first. The
+    // defaults of the parameters of the trait are not allowed on a function.
+    let mut lifetimes = Vec::new();
+    let mut types = Vec::new();
+    for param in trait_generics.params.iter().chain(&method.sig.generics.params) {
+        match param {
+            GenericParam::Lifetime(param) => lifetimes.push(param.to_token_stream()),
+            GenericParam::Type(param) => {
+                let mut param = param.clone();
+                param.eq_token = None;
+                param.default = None;
+                types.push(param.to_token_stream());
+            }
+            GenericParam::Const(param) => types.push(param.to_token_stream()),
+        }
+    }
+    let (_, trait_ty_generics, _) = trait_generics.split_for_impl();
+    let predicates = trait_generics
+        .where_clause
+        .iter()
+        .chain(&method.sig.generics.where_clause)
+        .flat_map(|where_clause| &where_clause.predicates);
+
+    let mut inputs = Vec::new();
+    for input in &method.sig.inputs {
+        inputs.push(match input {
+            FnArg::Receiver(receiver) => {
+                let ty = &receiver.ty;
+                quote!(self: #ty)
+            }
+            FnArg::Typed(pat_type) => {
+                let mut pat_type = pat_type.clone();
+                if let Pat::Ident(pat) = &mut *pat_type.pat {
+                    pat.mutability = None;
+                }
+                pat_type.to_token_stream()
+            }
+        });
+    }
+    let output = match &method.sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => ty.to_token_stream(),
+    };
+    let result = Ident::new("result", Span::mixed_site());
+    // A method that takes `self` by value can only be called on a sized implementor, and the
+    // functions take it by value too.
+    let by_value = method
+        .sig
+        .receiver()
+        .is_some_and(|receiver| receiver.reference.is_none() && receiver.colon_token.is_none());
+    let unsized_bound = (!by_value).then(|| quote!(?Sized+));
+    let generics = quote!(
+        <#(#lifetimes,)* __Self: #unsized_bound #trait_ident #trait_ty_generics, #(#types),*>
+    );
+    let where_clause = quote!(where #(#predicates,)*);
+
+    let requires = if requires.is_empty() { quote!(true) } else { quote!(#((#requires))&&*) };
+    let ensures = ensures.iter().map(|pred| match pred {
+        Expr::Closure(closure) if closure.inputs.len() == 1 => {
+            let binding = &closure.inputs[0];
+            let body = &closure.body;
+            quote!({
+                let #binding = #result;
+                #body
+            })
+        }
+        pred => quote!((#pred)),
+    });
+    let ensures = if ensures.len() == 0 { quote!(true) } else { quote!(#(#ensures)&&*) };
+
+    let requires_ident = format_ident!("requires_{}", ident);
+    let ensures_ident = format_ident!("ensures_{}", ident);
+    let attrs: Vec<_> = method.attrs.iter().filter(|attr| attr.path().is_ident("cfg")).collect();
+    let stability = staged.then(|| quote!(#[unstable(feature = "ub_checks", issue = "none")]));
+    [
+        (requires_ident, quote!(#(#inputs),*), requires),
+        (ensures_ident, quote!(#(#inputs,)* #result: &#output), ensures),
+    ]
+    .map(|(fn_ident, inputs, body)| {
+        // The marker is added after the parameters and the contract were renamed.
+        let generics = rename_self(generics.clone());
+        let inputs = rename_self(inputs);
+        let where_clause = rename_self(where_clause.clone());
+        let body = rename_self(body);
+        quote!(
+            #[doc(hidden)]
+            #[inline]
+            #[allow(unused_variables)]
+            #(#attrs)*
+            #stability
+            pub fn #fn_ident #generics(_: #marker_ty, #inputs) -> bool #where_clause {
+                #body
+            }
+        )
+    })
+}
+
+/// Rename `self` to `__self` and `Self` to `__Self`, so that a contract that was written in the
+/// trait can be evaluated outside of it. A `self` that starts a path, as in `self::f`, is kept.
+fn rename_self(tokens: TokenStream) -> TokenStream {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut renamed = TokenStream::new();
+    while let Some(token) = tokens.next() {
+        let token = match token {
+            TokenTree::Ident(ident) if ident == "Self" => Ident::new("__Self", ident.span()).into(),
+            TokenTree::Ident(ident)
+                if ident == "self"
+                    && !matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint) =>
+            {
+                Ident::new("__self", ident.span()).into()
+            }
+            TokenTree::Group(group) => {
+                let mut renamed = Group::new(group.delimiter(), rename_self(group.stream()));
+                renamed.set_span(group.span());
+                renamed.into()
+            }
+            token => token,
+        };
+        renamed.extend([token]);
+    }
+    renamed
+}
+fn parse_pred(attr: &Attribute, sig: &Signature, is_ensures: bool) -> Expr {
+    let tokens = match attr.meta.require_list() {
+        Ok(list) => list.tokens.clone(),
+        Err(err) => abort!(attr, err),
+    };
+    let pred = validate::contract(tokens, sig, is_ensures);
+    if mentions_old(pred.to_token_stream()) {
+        abort!(attr, "`old` cannot be used in the contract of a trait method");
+    }
+    pred
+}
+
+/// Whether a contract refers to `old(...)`.
+pub(crate) fn mentions_old(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "old" => {
+                if matches!(tokens.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+                {
+                    return true;
+                }
+            }
+            TokenTree::Group(group) if mentions_old(group.stream()) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn has_stability(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("stable") || attr.path().is_ident("unstable"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the preconditions that a method inherits when it is entered.
+    fn check(mut fn_item: ItemFn, inherited: &Inherited) -> ItemFn {
+        let requires = inherited.requires();
+        fn_item.block.stmts.insert(0, parse_quote!(assert!(#requires);));
+        fn_item
+    }
+
+    fn expand(attr: TokenStream, item: TokenStream) -> String {
+        contracts(attr, item, Some(check), check).to_string()
+    }
+
+    #[test]
+    fn trait_contracts_stay_in_the_crate() {
+        let expanded = expand(
+            quote!(),
+            quote!(
+                #[stable(feature = "alloc_module", since = "1.28.0")]
+                pub unsafe trait GlobalAlloc {
+                    #[safety::requires(layout.size() != 0)]
+                    unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+                }
+            ),
+        );
+        let module = quote!(
+            pub(crate) mod __safety_contracts_GlobalAlloc {
+                use super::*;
+            }
+        )
+        .to_string();
+        assert!(expanded.contains(module.trim_end_matches(" }")), "{expanded}");
+        // The trait is the only public item.
+        assert_eq!(expanded.matches("pub unsafe trait GlobalAlloc").count(), 1);
+        assert!(!expanded.contains("pub const") && !expanded.contains("pub struct GlobalAlloc"));
+        let requires = quote!(
+            pub fn requires_alloc<__Self: ?Sized + GlobalAlloc,>(
+                _: ::core::marker::PhantomData<(*const __Self,)>,
+                __self: &__Self,
+                layout: Layout
+            ) -> bool
+        );
+        assert!(expanded.contains(&requires.to_string()), "{expanded}");
+    }
+
+    #[test]
+    fn by_value_receiver_needs_a_sized_implementor() {
+        let expanded = expand(
+            quote!(),
+            quote!(
+                pub unsafe trait SliceIndex<T: ?Sized> {
+                    #[safety::requires(!slice.is_null())]
+                    unsafe fn get_unchecked(self, slice: *const T) -> *const Self::Output;
+                }
+            ),
+        );
+        let requires = quote!(
+            pub fn requires_get_unchecked<__Self: SliceIndex<T>, T: ?Sized>(
+                _: ::core::marker::PhantomData<(*const __Self, *const T,)>,
+                __self: __Self,
+                slice: *const T
+            ) -> bool
+        );
+        assert!(expanded.contains(&requires.to_string()), "{expanded}");
+    }
+
+    #[test]
+    fn impl_finds_contracts_next_to_the_trait() {
+        let expanded = expand(
+            quote!(),
+            quote!(
+                unsafe impl crate::slice::SliceIndex<[T]> for usize {
+                    unsafe fn get_unchecked(self, slice: *const [T]) -> *const T {}
+                }
+            ),
+        );
+        let requires =
+            quote!(crate::slice::__safety_contracts_SliceIndex::Contracts::requires_get_unchecked(
+                ::core::marker::PhantomData::<(*const Self, *const [T],)>,
+                ::core::clone::Clone::clone(&self),
+                ::core::clone::Clone::clone(&slice)
+            ));
+        assert!(expanded.contains(&requires.to_string()), "{expanded}");
+    }
+
+    #[test]
+    fn impl_and_method_from_another_crate() {
+        let from = quote!(from = core::ub_checks::contracts::GlobalAlloc);
+        let impl_expanded = expand(
+            from.clone(),
+            quote!(
+                unsafe impl GlobalAlloc for System {
+                    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {}
+                }
+            ),
+        );
+        let method_expanded = expand(
+            quote!(GlobalAlloc, #from),
+            quote!(
+                unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {}
+            ),
+        );
+        let requires = quote!(core::ub_checks::contracts::GlobalAlloc::requires_dealloc(
+            ::core::marker::PhantomData::<(*const Self,)>,
+            ::core::clone::Clone::clone(&self),
+            ::core::clone::Clone::clone(&ptr),
+            ::core::clone::Clone::clone(&layout)
+        ));
+        assert!(impl_expanded.contains(&requires.to_string()), "{impl_expanded}");
+        assert!(method_expanded.contains(&requires.to_string()), "{method_expanded}");
+    }
+
+    #[test]
+    fn method_names_its_trait() {
+        let expanded = expand(
+            quote!(Step),
+            quote!(
+                unsafe fn forward_unchecked(start: Self, n: usize) -> Self {}
+            ),
+        );
+        let requires = quote!(__safety_contracts_Step::Contracts::requires_forward_unchecked(
+            ::core::marker::PhantomData::<(*const Self,)>,
+            ::core::clone::Clone::clone(&start),
+            ::core::clone::Clone::clone(&n)
+        ));
+        assert!(expanded.contains(&requires.to_string()), "{expanded}");
+    }
+}
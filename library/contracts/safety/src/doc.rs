@@ -0,0 +1,117 @@
+//! Documentation of the contracts, which is emitted for every tool so that the contract that is
+//! verified is shown next to the `# Safety` section of the documentation.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{parse_quote, Attribute};
+
+/// The heading under which the clauses of the contract are listed.
+const HEADING: &str = " # Contract";
+
+/// Add a clause of the contract to the documentation of an item, such as `Requires` for a
+/// precondition.
+///
+/// The first clause also adds the heading, so the clauses are listed in the order in which the
+/// attributes are written, which is the order in which they are expanded.
+pub(crate) fn document(attrs: &mut Vec<Attribute>, clause: &str, tokens: TokenStream) {
+    let line = if tokens.is_empty() {
+        format!(" * {clause}: nothing")
+    } else {
+        format!(" * {clause}: `{}`", render(tokens))
+    };
+    document_line(attrs, &line);
+}
+
+/// Add a clause whose value is prose rather than code, such as the reason why a function is
+/// trusted.
+pub(crate) fn document_text(attrs: &mut Vec<Attribute>, clause: &str, text: &str) {
+    document_line(attrs, &format!(" * {clause}: {text}"));
+}
+
+fn document_line(attrs: &mut Vec<Attribute>, line: &str) {
+    let has_heading = attrs.iter().any(|attr| {
+        attr.path().is_ident("doc")
+            && matches!(&attr.meta, syn::Meta::NameValue(meta)
+                if meta.value.to_token_stream().to_string() == format!("{HEADING:?}"))
+    });
+    if !has_heading {
+        attrs.push(parse_quote!(#[doc = ""]));
+        attrs.push(parse_quote!(#[doc = #HEADING]));
+        attrs.push(parse_quote!(#[doc = ""]));
+    }
+    attrs.push(parse_quote!(#[doc = #line]));
+}
+
+/// Render an expression the way it would usually be written, which is used to report which
+/// contract was violated. `TokenStream::to_string` separates every token with a space instead.
+pub(crate) fn render(tokens: TokenStream) -> String {
+    let mut result = String::new();
+    // Whether the next token should be glued to the previous one.
+    let mut glue = true;
+    // Whether the previous token was an operand, which distinguishes `a - b` from `-b`.
+    let mut operand = false;
+    // Whether the previous token is the first character of a multi-character operator.
+    let mut joint = false;
+    // How many `<` of a turbofish or of a type are still open.
+    let mut turbofish = 0;
+    // Whether we are between the `|` of closure parameters, where `<` always opens generics.
+    let mut closure_params = false;
+    for token in tokens {
+        let (text, glue_before, glue_after, is_operand) = match &token {
+            TokenTree::Ident(ident) => (ident.to_string(), false, false, ident != "as"),
+            TokenTree::Literal(lit) => (lit.to_string(), false, false, true),
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::None => ("", ""),
+                };
+                let call = operand && group.delimiter() != Delimiter::Brace;
+                (format!("{open}{}{close}", render(group.stream())), call, false, true)
+            }
+            TokenTree::Punct(punct) => {
+                let ch = punct.as_char();
+                let next_joint = punct.spacing() == Spacing::Joint;
+                let (glue_before, glue_after) = match ch {
+                    _ if joint => (true, next_joint || ch == ':' || ch == '.'),
+                    ':' if next_joint => (true, true),
+                    '|' if closure_params => {
+                        closure_params = false;
+                        (true, false)
+                    }
+                    '|' if !operand && !next_joint => {
+                        closure_params = true;
+                        (false, true)
+                    }
+                    '<' if closure_params || result.ends_with("::") => {
+                        turbofish += 1;
+                        (true, true)
+                    }
+                    '>' if turbofish > 0 => {
+                        turbofish -= 1;
+                        (true, false)
+                    }
+                    '.' => (true, true),
+                    '!' if operand && !next_joint => (true, true),
+                    ',' | ';' | ':' | '?' => (true, false),
+                    '!' | '&' | '*' | '-' if !operand => (false, true),
+                    _ => (false, next_joint),
+                };
+                let is_operand = ch == '?' || (ch == '>' && glue_before && !joint);
+                joint = next_joint;
+                (ch.to_string(), glue_before, glue_after, is_operand)
+            }
+        };
+        if !matches!(token, TokenTree::Punct(_)) {
+            joint = false;
+        }
+        if !result.is_empty() && !glue && !glue_before {
+            result.push(' ');
+        }
+        result.push_str(&text);
+        glue = glue_after;
+        operand = is_operand;
+    }
+    result
+}
@@ -0,0 +1,225 @@
+//! Tool independent part of the `auto_harness` attribute, which generates the harnesses that
+//! verify the contract of a function for each type it is instantiated with.
+
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::abort;
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{bracketed, FnArg, GenericParam, Ident, ItemFn, Token, Type, TypeReference};
+
+/// The arguments of `auto_harness`: `types = [u8, char, ()]`, which can be omitted for functions
+/// that are not generic.
+pub(crate) struct AutoHarness {
+    types: Vec<Type>,
+}
+
+impl Parse for AutoHarness {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(AutoHarness { types: Vec::new() });
+        }
+        let name: Ident = input.parse()?;
+        if name != "types" {
+            return Err(syn::Error::new(name.span(), "expected `types = [...]`"));
+        }
+        input.parse::<Token![=]>()?;
+        let content;
+        bracketed!(content in input);
+        let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+        Ok(AutoHarness { types: types.into_iter().collect() })
+    }
+}
+
+/// A harness of a function.
+#[cfg_attr(not(kani_host), allow(dead_code))]
+pub(crate) struct Harness {
+    pub(crate) name: Ident,
+    /// The statements that call the function with arbitrary arguments.
+    pub(crate) body: TokenStream,
+}
+
+/// Generate a harness for each type of `auto_harness`, or a single one if the function is not
+/// generic, where `any` is the expression that produces an arbitrary value of any type.
+///
+/// Pointer and reference arguments point to distinct locals that hold arbitrary values, so they
+/// are valid, aligned and do not overlap. The other arguments are arbitrary values.
+pub(crate) fn harnesses(fn_item: &ItemFn, attr: AutoHarness, any: &TokenStream) -> Vec<Harness> {
+    let sig = &fn_item.sig;
+    let mut type_params = Vec::new();
+    for param in &sig.generics.params {
+        match param {
+            GenericParam::Type(param) => type_params.push(&param.ident),
+            GenericParam::Const(param) => {
+                abort!(param, "`auto_harness` does not support const generic parameters")
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    let instances = match (type_params.as_slice(), attr.types.is_empty()) {
+        ([], true) => vec![None],
+        ([_], false) => attr.types.into_iter().map(Some).collect(),
+        ([], false) => abort!(attr.types[0], "`{}` is not generic", sig.ident),
+        ([param], true) => abort!(
+            param,
+            "`auto_harness` needs the types to instantiate `{}` with", param;
+            help = "write `#[safety::auto_harness(types = [...])]`"
+        ),
+        (params, _) => {
+            abort!(params[1], "`auto_harness` supports at most one type parameter")
+        }
+    };
+
+    let fn_name = &sig.ident;
+    instances
+        .into_iter()
+        .map(|ty| {
+            let mut stmts = Vec::new();
+            let mut args = Vec::new();
+            for (idx, input) in sig.inputs.iter().enumerate() {
+                let FnArg::Typed(pat_type) = input else {
+                    abort!(input, "`auto_harness` can only be applied to free functions")
+                };
+                let mut arg_ty = (*pat_type.ty).clone();
+                Instantiate { param: type_params.first().copied(), ty: ty.as_ref() }
+                    .visit_type_mut(&mut arg_ty);
+                let (stmt, arg) = argument(&arg_ty, idx, any);
+                stmts.push(stmt);
+                args.push(arg);
+            }
+            let turbofish = ty.as_ref().map(|ty| quote!(::<#ty>));
+            let mut call = quote!(super::#fn_name #turbofish(#(#args),*));
+            if sig.unsafety.is_some() {
+                call = quote!(unsafe { #call });
+            }
+            let name = match &ty {
+                Some(ty) => format_ident!("check_{}_{}", fn_name, suffix(ty)),
+                None => format_ident!("check_{}", fn_name),
+            };
+            Harness {
+                name,
+                body: quote!(
+                    #(#stmts)*
+                    let _ = #call;
+                ),
+            }
+        })
+        .collect()
+}
+
+/// The statement that declares an argument, and the argument to pass to the function.
+fn argument(ty: &Type, idx: usize, any: &TokenStream) -> (TokenStream, TokenStream) {
+    let arg = Ident::new(&format!("arg_{idx}"), Span::mixed_site());
+    match ty {
+        Type::Ptr(ptr) => {
+            let elem = &ptr.elem;
+            if ptr.mutability.is_some() {
+                (quote!(let mut #arg: #elem = #any;), quote!(&mut #arg as *mut #elem))
+            } else {
+                (quote!(let #arg: #elem = #any;), quote!(&#arg as *const #elem))
+            }
+        }
+        Type::Reference(reference) => {
+            let elem = &reference.elem;
+            if reference.mutability.is_some() {
+                (quote!(let mut #arg: #elem = #any;), quote!(&mut #arg))
+            } else {
+                (quote!(let #arg: #elem = #any;), quote!(&#arg))
+            }
+        }
+        ty => (quote!(let #arg: #ty = #any;), quote!(#arg)),
+    }
+}
+
+/// A name for the harness of an instance, such as `u8`, `unit` for `()`, or `core_num_nonzeroi32`
+/// for `core::num::NonZeroI32`.
+fn suffix(ty: &Type) -> String {
+    if matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty()) {
+        return "unit".to_string();
+    }
+    let name = ty.to_token_stream().to_string().to_lowercase();
+    let words: Vec<_> =
+        name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    words.join("_")
+}
+
+/// Replace the type parameter of the function in the type of an argument, and drop the lifetimes
+/// of references, which are not in scope in the harness.
+struct Instantiate<'a> {
+    param: Option<&'a Ident>,
+    ty: Option<&'a Type>,
+}
+
+impl VisitMut for Instantiate<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let (Type::Path(path), Some(param), Some(instance)) = (&*ty, self.param, self.ty) {
+            if path.qself.is_none() && path.path.is_ident(param) {
+                *ty = instance.clone();
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        reference.lifetime = None;
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn expand(fn_item: ItemFn, attr: TokenStream) -> Vec<(String, String)> {
+        let attr = syn::parse2(attr).unwrap();
+        harnesses(&fn_item, attr, &quote!(any()))
+            .into_iter()
+            .map(|harness| (harness.name.to_string(), harness.body.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn generic_function_with_pointers() {
+        let fn_item = parse_quote!(
+            pub const unsafe fn typed_swap<T>(x: *mut T, y: *mut T) {}
+        );
+        let harnesses = expand(fn_item, quote!(types = [u8, (), core::num::NonZeroU64]));
+        let expected = |name: &str, ty: TokenStream| {
+            let body = quote!(
+                let mut arg_0: #ty = any();
+                let mut arg_1: #ty = any();
+                let _ = unsafe {
+                    super::typed_swap::<#ty>(&mut arg_0 as *mut #ty, &mut arg_1 as *mut #ty)
+                };
+            );
+            (name.to_string(), body.to_string())
+        };
+        assert_eq!(
+            harnesses,
+            [
+                expected("check_typed_swap_u8", quote!(u8)),
+                expected("check_typed_swap_unit", quote!(())),
+                expected("check_typed_swap_core_num_nonzerou64", quote!(core::num::NonZeroU64)),
+            ]
+        );
+    }
+
+    #[test]
+    fn function_with_references_and_values() {
+        let fn_item = parse_quote!(
+            pub fn copy_prefix<'a>(dst: &'a mut [u8; 4], src: &'a [u8; 4], len: usize) -> usize {}
+        );
+        let harnesses = expand(fn_item, quote!());
+        let body = quote!(
+            let mut arg_0: [u8; 4] = any();
+            let arg_1: [u8; 4] = any();
+            let arg_2: usize = any();
+            let _ = super::copy_prefix(&mut arg_0, &arg_1, arg_2);
+        );
+        assert_eq!(harnesses, [("check_copy_prefix".to_string(), body.to_string())]);
+    }
+}
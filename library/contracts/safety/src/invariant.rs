@@ -0,0 +1,66 @@
+//! Tool independent part of the `invariant` attribute.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{Attribute, Expr, Generics, Ident, Item};
+
+use crate::is_safety_attr;
+
+/// Generate the `is_safe` method of a type annotated with `#[safety::invariant(expr)]`.
+///
+/// Other `invariant` attributes of the same item are merged into this one, since a type can
+/// only have one `is_safe` method. The method is a `const fn`, so that it can be used in the
+/// contracts of `const` functions, which means the invariant must be callable in a const context.
+/// It is only visible in the crate of the type, and other crates, such as the harnesses of
+/// `alloc` that take the types of `core` as inputs, call it through the impl of
+/// `core::ub_checks::Invariant` that is generated for the type.
+pub(crate) fn type_invariant(pred: Expr, mut item: Item) -> TokenStream {
+    let (attrs, ident, generics) = match &mut item {
+        Item::Struct(item) => (&mut item.attrs, &item.ident, &item.generics),
+        Item::Enum(item) => (&mut item.attrs, &item.ident, &item.generics),
+        Item::Union(item) => (&mut item.attrs, &item.ident, &item.generics),
+        _ => abort!(item, "`invariant` with a condition can only be applied to a type definition"),
+    };
+    let mut preds = vec![pred];
+    preds.extend(take_invariants(attrs));
+    let is_safe = is_safe_impl(ident, generics, &preds);
+    quote!(
+        #item
+        #is_safe
+    )
+}
+
+fn is_safe_impl(ident: &Ident, generics: &Generics, preds: &[Expr]) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Check the safety invariant of this type, as declared with `#[safety::invariant]`.
+            #[allow(dead_code)] // only used by contracts and proof harnesses
+            #[inline]
+            pub(crate) const fn is_safe(&self) -> bool {
+                #((#preds))&&*
+            }
+        }
+
+        impl #impl_generics ::core::ub_checks::Invariant for #ident #ty_generics #where_clause {
+            #[inline]
+            fn is_safe(&self) -> bool {
+                Self::is_safe(self)
+            }
+        }
+    )
+}
+
+/// Remove the remaining `invariant` attributes from `attrs`, and return their conditions.
+fn take_invariants(attrs: &mut Vec<Attribute>) -> Vec<Expr> {
+    let mut preds = Vec::new();
+    attrs.retain(|attr| {
+        let is_invariant = is_safety_attr(attr, "invariant");
+        if is_invariant {
+            preds.push(attr.parse_args().unwrap_or_else(|err| abort!(attr, err)));
+        }
+        !is_invariant
+    });
+    preds
+}
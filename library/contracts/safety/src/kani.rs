@@ -1,15 +1,227 @@
 use proc_macro::{TokenStream};
-use quote::{quote, format_ident};
-use syn::{ItemFn, parse_macro_input};
+use proc_macro2::Span;
+use proc_macro_error::{abort, abort_call_site};
+use quote::{quote, format_ident, ToTokens};
+use syn::{Expr, ExprLoop, ExprWhile, Ident, Item, ItemFn, ReturnType, parse_macro_input, parse_quote};
+
+use crate::contracts::{mentions_old, Inherited};
+use crate::doc::document;
+use crate::harness::{harnesses, AutoHarness};
+use crate::invariant::type_invariant;
+use crate::is_safety_attr;
+use crate::quantifier::Quantifier;
+use crate::validate::contract;
 
 pub(crate) fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
     rewrite_attr(attr, item, "requires")
 }
 
+/// The postcondition is forwarded as is, since Kani already understands the `|result| ...`
+/// closure form, and snapshots the arguments of `old(...)` before calling the function.
 pub(crate) fn ensures(attr: TokenStream, item: TokenStream) -> TokenStream {
     rewrite_attr(attr, item, "ensures")
 }
 
+pub(crate) fn modifies(attr: TokenStream, item: TokenStream) -> TokenStream {
+    rewrite_attr(attr, item, "modifies")
+}
+
+/// On a type, `invariant` generates an `is_safe` method that harnesses use to assume the
+/// invariant of their inputs and to assert the one of their outputs.
+///
+/// On an `impl` block or a method, it is a no-op, since the invariant is only checked by
+/// harnesses.
+pub(crate) fn invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as Item);
+    if let Item::Impl(_) | Item::Fn(_) = item {
+        if !attr.is_empty() {
+            abort_call_site!("`invariant` does not take a condition on an `impl` block or a method");
+        }
+        return item.into_token_stream().into();
+    }
+    let pred = parse_macro_input!(attr as Expr);
+    type_invariant(pred, item).into()
+}
+
+/// Kani only accepts one loop invariant per loop, so the ones of the other `loop_invariant`
+/// attributes of the same loop are conjoined with this one.
+pub(crate) fn loop_invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let pred = parse_macro_input!(attr as Expr);
+    let mut expr = crate::validate::invariant_loop(item.into());
+    let attrs = match &mut expr {
+        Expr::While(ExprWhile { attrs, .. }) | Expr::Loop(ExprLoop { attrs, .. }) => attrs,
+        _ => unreachable!(),
+    };
+    let mut preds = vec![pred];
+    attrs.retain(|attr| {
+        let is_loop_invariant = is_safety_attr(attr, "loop_invariant");
+        if is_loop_invariant {
+            preds.push(attr.parse_args().unwrap_or_else(|err| abort!(attr, err)));
+        }
+        !is_loop_invariant
+    });
+    quote!(
+        #[kani_core::loop_invariant(#((#preds))&&*)]
+        #expr
+    ).into()
+}
+
+/// With Kani, `contracts` turns the contract that each method of a trait impl inherits from the
+/// trait into a contract of the method, so that every impl gets its own proof obligations. The
+/// provided methods of the trait are not instrumented.
+pub(crate) fn contracts(attr: TokenStream, item: TokenStream) -> TokenStream {
+    crate::contracts::contracts(attr.into(), item.into(), None, inherited_contract).into()
+}
+
+fn inherited_contract(mut fn_item: ItemFn, inherited: &Inherited) -> ItemFn {
+    let requires = inherited.requires();
+    let result = Ident::new("result", Span::call_site());
+    let ensures = inherited.ensures(&result);
+    fn_item.attrs.push(parse_quote!(#[kani_core::requires(#requires)]));
+    fn_item.attrs.push(parse_quote!(#[kani_core::ensures(|#result| #ensures)]));
+    fn_item
+}
+
+/// With Kani, `trusted` replaces the body of the function with a stand-in that only follows its
+/// contract: the preconditions are asserted, since the callers still have to establish them, and
+/// the result is any value that satisfies the postconditions, which are assumed instead of being
+/// proof obligations. The contract is also forwarded to Kani, so that the harnesses of the callers
+/// can name the function in `stub_verified`. The contract of the function has to be written after
+/// `trusted`, so that it is consumed here.
+///
+/// The stand-in does not write to memory, so only the functions whose effect is their result
+/// should be trusted.
+pub(crate) fn trusted(attr: TokenStream, item: TokenStream) -> TokenStream {
+    crate::trusted::trusted(attr.into(), item.into(), Some(verified_stub)).into()
+}
+
+fn verified_stub(mut fn_item: ItemFn) -> ItemFn {
+    let mut requires = Vec::new();
+    let mut ensures = Vec::new();
+    let mut clauses = Vec::new();
+    fn_item.attrs.retain(|attr| {
+        let path = attr.path();
+        if path.segments.len() == 2 && path.segments[0].ident == "kani_core" {
+            abort!(attr, "the contract of a trusted function must come after `trusted`");
+        }
+        let is_ensures = is_safety_attr(attr, "ensures");
+        if !is_ensures && !is_safety_attr(attr, "requires") {
+            return true;
+        }
+        let list = attr.meta.require_list().unwrap_or_else(|err| abort!(attr, err));
+        let tokens = list.tokens.clone();
+        let pred = contract(tokens.clone(), &fn_item.sig, is_ensures);
+        if is_ensures {
+            if mentions_old(tokens.clone()) {
+                abort!(attr, "`old` cannot be used in the contract of a trusted function");
+            }
+            clauses.push(("Ensures", tokens));
+            ensures.push(pred);
+        } else {
+            clauses.push(("Requires", tokens));
+            requires.push(pred);
+        }
+        false
+    });
+    for (clause, tokens) in clauses {
+        document(&mut fn_item.attrs, clause, tokens);
+    }
+    for pred in &requires {
+        fn_item.attrs.push(parse_quote!(#[kani_core::requires(#pred)]));
+    }
+    for pred in &ensures {
+        fn_item.attrs.push(parse_quote!(#[kani_core::ensures(#pred)]));
+    }
+    if fn_item.sig.constness.is_some() {
+        // A `const fn` cannot call `assert` and `assume`, so its body is kept, and the harnesses
+        // of its callers assume its contract with `stub_verified`.
+        return fn_item;
+    }
+    let ensures = ensures.iter().map(|pred| match pred {
+        Expr::Closure(closure) if closure.inputs.len() == 1 => {
+            let binding = &closure.inputs[0];
+            let body = &closure.body;
+            quote!({
+                let #binding = &result;
+                #body
+            })
+        }
+        pred => quote!(#pred),
+    });
+    let output = match &fn_item.sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    // The parameters are only used by the contract, if at all.
+    fn_item.attrs.push(parse_quote!(#[allow(unused_variables, unused_mut)]));
+    fn_item.block = parse_quote!({
+        #(::core::kani::assert(#requires, "trusted precondition");)*
+        let result: #output = ::core::kani::internal::any_modifies();
+        #(::core::kani::assume(#ensures);)*
+        result
+    });
+    fn_item
+}
+
+/// With Kani, `proof_for_contract` is forwarded as is.
+pub(crate) fn proof_for_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let item = proc_macro2::TokenStream::from(item);
+    quote!(#[kani_core::proof_for_contract(#attr)] #item).into()
+}
+
+/// And so is `proof`.
+pub(crate) fn proof(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = proc_macro2::TokenStream::from(item);
+    quote!(#[kani_core::proof] #item).into()
+}
+
+/// With Kani, `auto_harness` generates a `proof_for_contract` harness for each type the function
+/// is instantiated with, in a hidden module next to the function.
+pub(crate) fn auto_harness(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AutoHarness);
+    let fn_item = parse_macro_input!(item as ItemFn);
+    let fn_name = &fn_item.sig.ident;
+    let harnesses = harnesses(&fn_item, attr, &quote!(::core::kani::any()));
+    let names = harnesses.iter().map(|harness| &harness.name);
+    let bodies = harnesses.iter().map(|harness| &harness.body);
+    let module = format_ident!("__safety_harnesses_{}", fn_name);
+    let cfg = fn_item.attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+    quote!(
+        #fn_item
+
+        #[doc(hidden)]
+        #(#cfg)*
+        mod #module {
+            use super::*;
+
+            #(
+                #[kani_core::proof_for_contract(super::#fn_name)]
+                pub fn #names() {
+                    #bodies
+                }
+            )*
+        }
+    ).into()
+}
+
+/// Quantifiers are lowered to the ones of Kani, which take the bounds of the range as a pair.
+pub(crate) fn forall(item: TokenStream) -> TokenStream {
+    quantifier(item, "forall")
+}
+
+pub(crate) fn exists(item: TokenStream) -> TokenStream {
+    quantifier(item, "exists")
+}
+
+fn quantifier(item: TokenStream, name: &str) -> TokenStream {
+    let Quantifier { var, start, end, pred } = parse_macro_input!(item as Quantifier);
+    let quantifier = format_ident!("{}", name);
+    quote!(
+        kani_core::#quantifier!(|#var in (#start, #end)| #pred)
+    ).into()
+}
+
 fn rewrite_attr(attr: TokenStream, item: TokenStream, name: &str) -> TokenStream {
     let args = proc_macro2::TokenStream::from(attr);
     let fn_item = parse_macro_input!(item as ItemFn);
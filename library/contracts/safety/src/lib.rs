@@ -3,23 +3,160 @@
 
 use proc_macro::TokenStream;
 use proc_macro_error::proc_macro_error;
+use quote::ToTokens;
+use syn::{parse_macro_input, Attribute, DeriveInput, ItemFn};
+
+mod arbitrary;
+mod contracts;
+mod doc;
+mod harness;
+mod invariant;
+mod predicate;
+mod quantifier;
+mod trusted;
+mod valid_bits;
+mod validate;
 
 #[cfg(kani_host)]
 #[path = "kani.rs"]
 mod tool;
 
-#[cfg(not(kani_host))]
+#[cfg(all(safety_random, not(kani_host)))]
+#[path = "random.rs"]
+mod tool;
+
+#[cfg(not(any(kani_host, safety_random)))]
 #[path = "runtime.rs"]
 mod tool;
 
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    validated(&attr, &item, false);
+    let item = documented("Requires", &attr, item);
     tool::requires(attr, item)
 }
 
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn ensures(attr: TokenStream, item: TokenStream) -> TokenStream {
+    validated(&attr, &item, true);
+    let item = documented("Ensures", &attr, item);
     tool::ensures(attr, item)
 }
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn modifies(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = documented("Modifies", &attr, item);
+    tool::modifies(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::invariant(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn contracts(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::contracts(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn loop_invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::loop_invariant(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn trusted(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::trusted(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn auto_harness(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::auto_harness(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn proof_for_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::proof_for_contract(attr, item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tool::proof(attr, item)
+}
+
+/// Run a harness with pseudo-random inputs, which is only needed by the crates without unit tests
+/// under the randomized backend.
+#[cfg(all(safety_random, not(kani_host)))]
+#[proc_macro_error]
+#[proc_macro]
+pub fn run_harness(item: TokenStream) -> TokenStream {
+    tool::run_harness(item)
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn predicate(attr: TokenStream, item: TokenStream) -> TokenStream {
+    predicate::predicate(attr.into(), item.into()).into()
+}
+
+#[proc_macro_error]
+#[proc_macro]
+pub fn forall(item: TokenStream) -> TokenStream {
+    tool::forall(item)
+}
+
+#[proc_macro_error]
+#[proc_macro]
+pub fn exists(item: TokenStream) -> TokenStream {
+    tool::exists(item)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(ValidBits)]
+pub fn valid_bits(item: TokenStream) -> TokenStream {
+    valid_bits::derive(parse_macro_input!(item as DeriveInput)).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Arbitrary)]
+pub fn arbitrary(item: TokenStream) -> TokenStream {
+    arbitrary::derive(parse_macro_input!(item as DeriveInput)).into()
+}
+
+/// Check the contract that `attr` declares against the signature of `item`, which is left to the
+/// tool to report if it is not a function.
+fn validated(attr: &TokenStream, item: &TokenStream, is_ensures: bool) {
+    if let Ok(fn_item) = syn::parse::<ItemFn>(item.clone()) {
+        validate::contract(attr.clone().into(), &fn_item.sig, is_ensures);
+    }
+}
+
+/// Add the clause of the contract that `attr` declares to the documentation of `item`, which is
+/// left to the tool to report if it is not a function.
+fn documented(clause: &str, attr: &TokenStream, item: TokenStream) -> TokenStream {
+    match syn::parse::<ItemFn>(item.clone()) {
+        Ok(mut fn_item) => {
+            doc::document(&mut fn_item.attrs, clause, attr.clone().into());
+            fn_item.into_token_stream().into()
+        }
+        Err(_) => item,
+    }
+}
+
+/// Whether `attr` is the attribute of this crate with the given name, used either as `name` or as
+/// `safety::name`.
+fn is_safety_attr(attr: &Attribute, name: &str) -> bool {
+    let path = attr.path();
+    path.segments.last().is_some_and(|segment| segment.ident == name)
+        && (path.segments.len() == 1 || path.segments[0].ident == "safety")
+}
@@ -0,0 +1,345 @@
+//! Implementation of the `predicate` attribute, which is the same for every tool.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, emit_error};
+use quote::{quote, ToTokens};
+use syn::visit::{self, Visit};
+use syn::{
+    BinOp, Expr, ExprAssign, ExprBinary, ExprCall, ExprMethodCall, ExprReference, FnArg, Item,
+    ItemFn, Macro, Path, PathArguments, ReturnType, Stmt, Type, TypeReference, Visibility,
+};
+
+use crate::doc::document_text;
+use crate::trusted::reason;
+
+/// Methods of `core` types that only inspect their receiver, and can therefore be called from a
+/// predicate even though they are not marked as such.
+const PURE_METHODS: &[&str] = &[
+    "abs_diff",
+    "addr",
+    "as_ptr",
+    "as_usize",
+    "cast",
+    "checked_add",
+    "checked_div",
+    "checked_mul",
+    "checked_rem",
+    "checked_sub",
+    "count_ones",
+    "count_zeros",
+    "get",
+    "is_aligned",
+    "is_aligned_to",
+    "is_ascii",
+    "is_char_boundary",
+    "is_empty",
+    "is_err",
+    "is_none",
+    "is_null",
+    "is_ok",
+    "is_power_of_two",
+    "is_safe",
+    "is_some",
+    "leading_zeros",
+    "len",
+    "overflowing_add",
+    "overflowing_mul",
+    "overflowing_sub",
+    "saturating_add",
+    "saturating_mul",
+    "saturating_sub",
+    "trailing_zeros",
+    "wrapping_add",
+    "wrapping_mul",
+    "wrapping_sub",
+];
+
+/// Free functions of `core` that can be called from a predicate.
+const PURE_FUNCTIONS: &[&str] = &["align_of", "align_of_val", "size_of", "size_of_val"];
+
+/// Macros that can be used in a predicate.
+const PURE_MACROS: &[&str] = &["cfg", "exists", "forall", "matches"];
+
+/// The predicates whose body was audited to have no side effects, and which can therefore be
+/// trusted with a `reason` instead of being checked. A predicate is only added here after its
+/// body has been reviewed, so that trusting a predicate is not decided by the predicate alone.
+const TRUSTED_PREDICATES: &[&str] = &[
+    // `core::ub_checks::shadow`, which queries the installed shadow memory.
+    "can_access",
+    "is_initialized",
+    "same_allocation",
+];
+
+/// A predicate is a `const fn` returning a `bool`, which can be used in the contracts of every
+/// tool. This rejects the predicates that could have side effects: the ones that take `&mut`
+/// parameters, mutate memory they do not own, or call functions that are not predicates
+/// themselves.
+///
+/// Calls to other predicates are checked at compile time, through the hidden struct with the same
+/// name that is emitted next to each predicate, so that importing a predicate imports it as well.
+/// The struct is never visible outside of the crate, so that it does not add a name to a public
+/// module, which means that a predicate can only call the predicates of its own crate. The other
+/// calls are restricted to a list of side-effect free functions and methods of `core`, and to
+/// constructors. Panics caused by arithmetic overflows or out of bounds indexing are not detected.
+///
+/// A predicate can select its implementation with `const_eval_select`, if both arms are
+/// predicates, or functions declared in its body, which are then checked as part of it and do not
+/// need to be `const`.
+///
+/// A predicate that queries state the checks cannot see through, such as the shadow memory of the
+/// runtime checks, can be trusted with `#[safety::predicate(reason = "...")]`, which justifies why
+/// it has no side effects, if it is listed in [`TRUSTED_PREDICATES`]. Its body is not checked,
+/// and it does not need to be `const`, so that it can be called from the runtime arm of other
+/// predicates.
+pub(crate) fn predicate(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let reason = (!attr.is_empty()).then(|| reason(attr));
+    let mut fn_item: ItemFn = syn::parse2(item).unwrap_or_else(|err| abort!(err.span(), err));
+    if let Some(reason) = &reason {
+        let ident = &fn_item.sig.ident;
+        if !TRUSTED_PREDICATES.contains(&ident.to_string().as_str()) {
+            emit_error!(
+                reason,
+                "`{}` is not an audited predicate", ident;
+                help = "add it to `TRUSTED_PREDICATES` in `safety` once its body has been reviewed"
+            );
+        }
+        document_text(&mut fn_item.attrs, "Trusted", &reason.value());
+    }
+    let sig = &fn_item.sig;
+    if sig.constness.is_none() && reason.is_none() {
+        emit_error!(sig.fn_token, "a predicate must be a `const fn`");
+    }
+    if let Some(unsafety) = sig.unsafety {
+        emit_error!(unsafety, "a predicate cannot be `unsafe`");
+    }
+    if !matches!(&sig.output, ReturnType::Type(_, ty) if ty.to_token_stream().to_string() == "bool")
+    {
+        emit_error!(sig.output, "a predicate must return a `bool`");
+    }
+    for input in &sig.inputs {
+        match input {
+            FnArg::Receiver(receiver) if is_mut_ref(&receiver.ty) => {
+                emit_error!(receiver, "a predicate cannot take `&mut self`");
+            }
+            FnArg::Typed(arg) if is_mut_ref(&arg.ty) => {
+                emit_error!(arg.ty, "a predicate cannot take a `&mut` parameter");
+            }
+            _ => {}
+        }
+    }
+
+    let local_fns = fn_item
+        .block
+        .stmts
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Item(Item::Fn(local)) => Some(local),
+            _ => None,
+        })
+        .collect();
+    let mut checker = PurityChecker { callees: Vec::new(), local_fns };
+    if reason.is_none() {
+        checker.visit_block(&fn_item.block);
+    }
+    let callees = checker.callees;
+
+    let ItemFn { attrs, vis, sig, block } = &fn_item;
+    // The marker is available under the same conditions as the predicate, but only in the crate.
+    let cfgs = attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+    let marker_vis = match vis {
+        Visibility::Public(_) => quote!(pub(crate)),
+        vis => vis.to_token_stream(),
+    };
+    let marker = &sig.ident;
+    quote!(
+        #(#attrs)*
+        #vis #sig {
+            #(let _ = ::core::marker::PhantomData::<#callees>;)*
+            #block
+        }
+
+        #[doc(hidden)]
+        #[allow(dead_code, non_camel_case_types)]
+        #(#cfgs)*
+        #marker_vis struct #marker {}
+    )
+}
+
+fn is_mut_ref(ty: &Type) -> bool {
+    matches!(ty, Type::Reference(TypeReference { mutability: Some(_), .. }))
+}
+
+/// Reports the expressions of a predicate that may have side effects, and collects the paths to
+/// the markers of the predicates it calls.
+struct PurityChecker<'ast> {
+    callees: Vec<Path>,
+    /// The functions declared in the body of the predicate.
+    local_fns: Vec<&'ast ItemFn>,
+}
+
+impl PurityChecker<'_> {
+    /// Check the arm of a `const_eval_select`, which is either a function declared in the body
+    /// of the predicate, or another predicate.
+    fn visit_arm(&mut self, arm: &Expr) {
+        let Expr::Path(arm) = arm else {
+            emit_error!(arm, "the arms of `const_eval_select` must be predicates");
+            return;
+        };
+        let local = match &arm.path.segments.iter().collect::<Vec<_>>()[..] {
+            [segment] => {
+                self.local_fns.iter().copied().find(|local| local.sig.ident == segment.ident)
+            }
+            _ => None,
+        };
+        let Some(local) = local else {
+            self.callees.push(marker(&arm.path));
+            return;
+        };
+        for input in &local.sig.inputs {
+            match input {
+                FnArg::Typed(arg) if is_mut_ref(&arg.ty) => {
+                    emit_error!(arg.ty, "a predicate cannot take a `&mut` parameter");
+                }
+                _ => {}
+            }
+        }
+        self.visit_block(&local.block);
+    }
+}
+
+/// The path to the marker of the predicate at `path`, which is the same path without generic
+/// arguments.
+fn marker(path: &Path) -> Path {
+    let mut path = path.clone();
+    path.segments.last_mut().unwrap().arguments = PathArguments::None;
+    path
+}
+
+impl<'ast> Visit<'ast> for PurityChecker<'ast> {
+    fn visit_expr_assign(&mut self, assign: &'ast ExprAssign) {
+        check_place(&assign.left);
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast ExprBinary) {
+        if matches!(
+            binary.op,
+            BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+                | BinOp::RemAssign(_)
+                | BinOp::BitXorAssign(_)
+                | BinOp::BitAndAssign(_)
+                | BinOp::BitOrAssign(_)
+                | BinOp::ShlAssign(_)
+                | BinOp::ShrAssign(_)
+        ) {
+            check_place(&binary.left);
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_expr_reference(&mut self, reference: &'ast ExprReference) {
+        if let Some(mutability) = reference.mutability {
+            emit_error!(mutability, "a predicate cannot borrow mutably");
+        }
+        visit::visit_expr_reference(self, reference);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        match &*call.func {
+            Expr::Path(func) if func.qself.is_none() => {
+                let name = func.path.segments.last().unwrap().ident.to_string();
+                if name == "const_eval_select" && call.args.len() == 3 {
+                    self.visit_expr(&call.args[0]);
+                    self.visit_arm(&call.args[1]);
+                    self.visit_arm(&call.args[2]);
+                    return;
+                }
+                let is_constructor = name.starts_with(|c: char| c.is_ascii_uppercase());
+                if !is_constructor && !PURE_FUNCTIONS.contains(&name.as_str()) {
+                    self.callees.push(marker(&func.path));
+                }
+            }
+            func => emit_error!(func, "a predicate can only call functions by their path"),
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        if !PURE_METHODS.contains(&call.method.to_string().as_str()) {
+            emit_error!(
+                call.method,
+                "`{}` is not known to be free of side effects, call a predicate by its path instead",
+                call.method
+            );
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        let name = mac.path.segments.last().map(|segment| segment.ident.to_string());
+        if !name.is_some_and(|name| PURE_MACROS.contains(&name.as_str())) {
+            emit_error!(
+                mac.path,
+                "a predicate cannot use macros that may panic or have side effects"
+            );
+        }
+        visit::visit_macro(self, mac);
+    }
+
+    fn visit_item(&mut self, _: &'ast Item) {
+        // Nested items are not part of the predicate.
+    }
+}
+
+/// Only variables can be assigned to, since the other places may be memory that the predicate
+/// does not own.
+fn check_place(place: &Expr) {
+    if !matches!(place, Expr::Path(path) if path.path.get_ident().is_some()) {
+        emit_error!(place, "a predicate can only assign to its own variables");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_stays_in_the_crate() {
+        let expanded = predicate(
+            TokenStream::new(),
+            quote!(
+                #[stable(feature = "rust1", since = "1.0.0")]
+                pub const fn can_dereference<T>(src: *const T) -> bool {
+                    is_valid_pointer(src as *const (), size_of::<T>(), align_of::<T>())
+                }
+            ),
+        )
+        .to_string();
+        let marker = quote!(
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            pub(crate) struct can_dereference {}
+        );
+        assert!(expanded.ends_with(&marker.to_string()), "{expanded}");
+        let callee = quote!(let _ = ::core::marker::PhantomData::<is_valid_pointer>;);
+        assert!(expanded.contains(&callee.to_string()), "{expanded}");
+    }
+
+    #[test]
+    fn audited_predicate_is_trusted() {
+        let expanded = predicate(
+            quote!(reason = "the shadow memory is only queried"),
+            quote!(
+                pub(crate) fn can_access(addr: usize, size: usize) -> bool {
+                    shadow_memory().map_or(true, |memory| memory.can_access(addr, size))
+                }
+            ),
+        )
+        .to_string();
+        assert!(expanded.contains("Trusted"), "{expanded}");
+        assert!(!expanded.contains("PhantomData"), "{expanded}");
+    }
+}
@@ -0,0 +1,50 @@
+//! Tool independent part of the `forall` and `exists` macros.
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, ExprRange, Ident, RangeLimits, Token};
+
+/// A quantified predicate, written `|var in start..end| pred`.
+///
+/// The range is half-open, and its bounds are `usize`. A bound that contains a `|` has to be
+/// wrapped in parentheses, since the first `|` after `in` ends the range.
+pub(crate) struct Quantifier {
+    pub(crate) var: Ident,
+    pub(crate) start: Expr,
+    pub(crate) end: Expr,
+    pub(crate) pred: Expr,
+}
+
+impl Parse for Quantifier {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![|]>()?;
+        let var = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let range = input.step(|cursor| {
+            let mut range = TokenStream::new();
+            let mut rest = *cursor;
+            while let Some((tt, next)) = rest.token_tree() {
+                if matches!(&tt, TokenTree::Punct(punct) if punct.as_char() == '|') {
+                    return Ok((range, rest));
+                }
+                range.extend([tt]);
+                rest = next;
+            }
+            Err(cursor.error("expected `|` after the range of the quantifier"))
+        })?;
+        let range: ExprRange = syn::parse2(range)?;
+        input.parse::<Token![|]>()?;
+        let pred = input.parse()?;
+        match range {
+            ExprRange {
+                start: Some(start),
+                end: Some(end),
+                limits: RangeLimits::HalfOpen(_),
+                ..
+            } => Ok(Quantifier { var, start: *start, end: *end, pred }),
+            range => {
+                Err(syn::Error::new_spanned(range, "expected a range of the form `start..end`"))
+            }
+        }
+    }
+}
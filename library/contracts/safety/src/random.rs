@@ -0,0 +1,213 @@
+//! The randomized backend, selected with `--cfg safety_random`, runs every `proof` and
+//! `proof_for_contract` harness with many pseudo-random inputs.
+//!
+//! The contracts are checked at runtime as with the runtime backend, except that a violated
+//! contract unwinds instead of aborting, since the library is built with `--cfg safety_random`
+//! too. A harness draws its inputs from `kani::any()`, which the library implements with a
+//! deterministic PRNG under that configuration. An input is rejected if the preconditions of the
+//! function under verification or a `kani::assume` do not hold, and any other panic, such as a
+//! violated postcondition, fails the test with the seed that reproduces it.
+//!
+//! In a crate that is built for its unit tests, such as `alloc`, a harness becomes a `#[test]`.
+//! Otherwise it stays a function, so that a crate without unit tests, such as `core`, can list
+//! its harnesses for its integration tests, which run them with [`run_harness`]. The options of a
+//! harness that only matter to a verifier, such as `kani::unwind` or `kani::stub_verified`, are
+//! dropped, since a test calls the functions themselves.
+//!
+//! A harness runs `SAFETY_ITERATIONS` inputs, 256 by default, whose seeds are derived from the
+//! path of the harness, so that every run draws the same ones. `SAFETY_SEED=<seed>` only runs the
+//! input of the given seed, to replay a failure. The contracts are only checked if the test is
+//! built with UB checks, which is the case with debug assertions.
+
+use proc_macro::TokenStream;
+use proc_macro2::Ident;
+use proc_macro_error::{abort, abort_call_site};
+use quote::{format_ident, quote, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, parse_quote, Attribute, Expr, ExprPath, ItemFn, Token};
+
+use crate::doc::render;
+use crate::harness::{harnesses, AutoHarness};
+
+// The lowering of `requires` and of the harnesses of the runtime backend is replaced here.
+#[allow(dead_code)]
+#[path = "runtime.rs"]
+mod runtime;
+
+pub(crate) use runtime::{
+    contracts, ensures, exists, forall, invariant, loop_invariant, modifies, trusted,
+};
+
+/// The preconditions are checked inline, so that their violation unwinds and can be told apart
+/// from other failures by the function name in its message.
+pub(crate) fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let pred = parse_macro_input!(attr as Expr);
+    let mut fn_item = parse_macro_input!(item as ItemFn);
+    let message = format!(
+        "unsafe precondition(s) violated: {} requires `{}`",
+        fn_item.sig.ident,
+        render(pred.to_token_stream())
+    );
+    let check = parse_quote!(
+        ::core::ub_checks::check_contract!(check_library_ub, #message, #pred);
+    );
+    fn_item.block.stmts.insert(0, check);
+    fn_item.into_token_stream().into()
+}
+
+/// A harness of a function runs with pseudo-random inputs, which its preconditions may reject.
+pub(crate) fn proof_for_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let target = parse_macro_input!(attr as ExprPath);
+    let fn_item = parse_macro_input!(item as ItemFn);
+    let Some(target) = target.path.segments.last() else { abort!(target, "expected a function") };
+    harness(fn_item, Some(&target.ident)).into()
+}
+
+/// A harness that does not verify a contract runs with pseudo-random inputs too, which only
+/// `kani::assume` rejects.
+pub(crate) fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        abort_call_site!("`proof` does not take arguments");
+    }
+    harness(parse_macro_input!(item as ItemFn), None).into()
+}
+
+/// With the randomized backend, `auto_harness` generates the same harnesses as with Kani. The
+/// module also lists them, for the crates without unit tests.
+pub(crate) fn auto_harness(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AutoHarness);
+    let fn_item = parse_macro_input!(item as ItemFn);
+    let fn_name = &fn_item.sig.ident;
+    let harnesses = harnesses(&fn_item, attr, &quote!(::core::kani::any()));
+    let names: Vec<_> = harnesses.iter().map(|harness| &harness.name).collect();
+    let items = harnesses.iter().map(|generated| {
+        let (name, body) = (&generated.name, &generated.body);
+        harness(parse_quote!(pub fn #name() { #body }), Some(fn_name))
+    });
+    let module = format_ident!("__safety_harnesses_{}", fn_name);
+    let cfg = fn_item.attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+    quote!(
+        #fn_item
+
+        #[doc(hidden)]
+        #(#cfg)*
+        pub(crate) mod #module {
+            use super::*;
+
+            #(#items)*
+
+            #[cfg(not(test))]
+            #[allow(dead_code)]
+            pub(crate) const HARNESSES: &[::core::kani::Harness] = &[#((
+                ::core::concat!(::core::module_path!(), "::", ::core::stringify!(#names)),
+                #names as fn(),
+            )),*];
+        }
+    )
+    .into()
+}
+
+/// Run a harness with pseudo-random inputs, given the path that its seeds are derived from and
+/// the harness, as in `run_harness!("core::ptr::verify::check_read_u128", check_read_u128)`.
+pub(crate) fn run_harness(item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(item with Punctuated::<Expr, Token![,]>::parse_terminated);
+    let args: Vec<_> = args.into_iter().collect();
+    let [name, harness] = &args[..] else {
+        abort_call_site!("expected the path of the harness and the harness")
+    };
+    run(name, harness).into()
+}
+
+/// The harness `fn_item`, which verifies the function `target` if any, as a `#[test]` in a crate
+/// that is built for its unit tests, and as a function that records its target otherwise.
+fn harness(fn_item: ItemFn, target: Option<&Ident>) -> proc_macro2::TokenStream {
+    if !fn_item.sig.inputs.is_empty() {
+        abort!(fn_item.sig.inputs, "a harness cannot take arguments");
+    }
+    let ItemFn { mut attrs, vis, sig, block } = fn_item;
+    attrs.retain(|attr| !is_verifier_option(attr));
+    let target = target.map(|target| {
+        let target = target.to_string();
+        quote!(::core::kani::target(#target);)
+    });
+    let body = quote!({ #target #block });
+    let name = &sig.ident;
+    let path = quote!(::core::concat!(::core::module_path!(), "::", ::core::stringify!(#name)));
+    let run = run(&path, &quote!(harness));
+    quote!(
+        #[cfg(not(test))]
+        #[allow(dead_code)]
+        #(#attrs)* #vis #sig #body
+
+        #[cfg(test)]
+        #[test]
+        #(#attrs)* #vis fn #name() {
+            fn harness() #body
+
+            #run
+        }
+    )
+}
+
+/// Whether `attr` is an option of a Kani harness that only matters to verification.
+fn is_verifier_option(attr: &Attribute) -> bool {
+    let path = attr.path();
+    path.segments.len() == 2
+        && path.segments[0].ident == "kani"
+        && ["unwind", "stub", "stub_verified", "solver"]
+            .iter()
+            .any(|option| path.segments[1].ident == option)
+}
+
+/// Run the harness `harness`, a `fn()`, with the inputs of the seeds derived from the path `name`.
+fn run(name: &impl ToTokens, harness: &impl ToTokens) -> proc_macro2::TokenStream {
+    quote!({
+        let name: &str = #name;
+        let harness: fn() = #harness;
+        ::core::assert!(
+            ::core::ub_checks::check_library_ub(),
+            "{name} needs UB checks to check the contracts, build it with debug assertions",
+        );
+        let replay = ::std::env::var("SAFETY_SEED").ok().map(|seed| {
+            let digits = seed.trim().trim_start_matches("0x");
+            u64::from_str_radix(digits, 16).expect("SAFETY_SEED is not a hexadecimal seed")
+        });
+        let iterations = match replay {
+            Some(_) => 1,
+            None => ::std::env::var("SAFETY_ITERATIONS")
+                .ok()
+                .map(|count| count.parse().expect("SAFETY_ITERATIONS is not a number"))
+                .unwrap_or(256),
+        };
+        fn message(payload: &(dyn ::core::any::Any + ::core::marker::Send)) -> &str {
+            match payload.downcast_ref::<&str>() {
+                Some(message) => message,
+                None => payload.downcast_ref::<::std::string::String>().map_or("", |s| s),
+            }
+        }
+        // The rejected inputs are not reported by the panic hook.
+        static QUIET: ::std::sync::Once = ::std::sync::Once::new();
+        QUIET.call_once(|| {
+            let hook = ::std::panic::take_hook();
+            ::std::panic::set_hook(::std::boxed::Box::new(move |info| {
+                if !::core::kani::is_rejection(message(info.payload())) {
+                    hook(info);
+                }
+            }));
+        });
+        let mut accepted = 0u64;
+        for iteration in 0..iterations {
+            let seed = replay.unwrap_or_else(|| ::core::kani::harness_seed(name, iteration));
+            ::core::kani::seed(seed);
+            let Err(payload) = ::std::panic::catch_unwind(harness) else {
+                accepted += 1;
+                continue;
+            };
+            let message = message(&*payload);
+            if !::core::kani::is_rejection(message) {
+                ::core::panic!("{name} failed: {message}\nreplay with SAFETY_SEED={seed:#x}");
+            }
+        }
+        ::core::assert!(accepted > 0, "{name} rejected all of its {iterations} inputs");
+    })
+}
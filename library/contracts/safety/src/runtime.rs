@@ -1,15 +1,836 @@
 use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenTree};
+use proc_macro_error::{abort, abort_call_site};
+use quote::{quote, ToTokens};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_macro_input, parse_quote, Expr, ExprLet, ExprLoop, ExprWhile, FnArg,
+    GenericParam, ImplItem, Item, ItemFn, ItemImpl, Lifetime, Meta, Pat, ReturnType, Signature,
+    Stmt, Type, Visibility,
+};
 
-/// For now, runtime requires is a no-op.
+use crate::contracts::{Inherited, Instrument};
+use crate::doc::{document, render};
+use crate::harness::{harnesses, AutoHarness};
+use crate::invariant::type_invariant;
+use crate::is_safety_attr;
+use crate::quantifier::Quantifier;
+
+/// At runtime, `requires` becomes an unsafe precondition check that is evaluated when the
+/// function is entered.
+///
+/// The check is emitted with `assert_unsafe_precondition!(check_library_ub, ...)`, which means it
+/// is only evaluated if the crate that monomorphizes the function has UB checks enabled, and that
+/// the contract expression has to be callable from a `const fn`, as for any other use of
+/// that macro.
+pub(crate) fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let pred = parse_macro_input!(attr as Expr);
+    let mut fn_item = parse_macro_input!(item as ItemFn);
+    let check = precondition_check(&fn_item.sig, &pred);
+    fn_item.block.stmts.insert(0, check);
+    fn_item.into_token_stream().into()
+}
+
+/// At runtime, `ensures` checks the value returned by the function before returning it.
+///
+/// The postcondition can be written as a closure that takes a reference to the returned value, as
+/// in `|result| result.is_some()`, or as a plain boolean expression. The check is enabled under
+/// the same conditions as the ones generated for `requires`. Every `return` in the body is
+/// checked, but early returns performed by the `?` operator are not.
+///
+/// The postcondition can also refer to `old(expr)`, which is the value of `expr` when the function
+/// was entered, after its preconditions were checked. The value is only computed when checks are
+/// enabled, by cloning it, or by copying it in a `const fn`.
+pub(crate) fn ensures(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let pred = parse_macro_input!(attr as Expr);
+    let fn_item = parse_macro_input!(item as ItemFn);
+    let message = format!(
+        "postcondition(s) violated: {} ensures `{}`",
+        fn_item.sig.ident,
+        render(pred.to_token_stream())
+    );
+    postcondition_check(fn_item, pred, &message).into_token_stream().into()
+}
+
+/// At runtime, `modifies` checks that the memory behind the reference and raw pointer arguments
+/// of the function that are not part of its write set is left unchanged.
+///
+/// The write set is the union of every `modifies` attribute of the function. Only the values
+/// without padding and interior mutability are checked, see `ub_checks::bytes_fingerprint`, and the
+/// memory behind a raw pointer is only read if it is in a live allocation of the installed shadow
+/// memory, since the function may be allowed to receive pointers that cannot be read. The checks
+/// are enabled under the same conditions as the ones generated for `requires`.
+pub(crate) fn modifies(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut fn_item = parse_macro_input!(item as ItemFn);
+    let mut write_set = idents(attr.into());
+    // The other `modifies` attributes are handled here, so they are documented here as well.
+    let (others, attrs) =
+        fn_item.attrs.drain(..).partition::<Vec<_>, _>(|attr| is_safety_attr(attr, "modifies"));
+    fn_item.attrs = attrs;
+    for attr in others {
+        let Meta::List(list) = attr.meta else { abort!(attr, "expected `modifies(...)`") };
+        write_set.extend(idents(list.tokens.clone()));
+        document(&mut fn_item.attrs, "Modifies", list.tokens);
+    }
+
+    let unlisted: Vec<_> = fn_item
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Receiver(receiver)
+                if receiver.reference.is_some() && receiver.colon_token.is_none() =>
+            {
+                Some((Ident::new("self", Span::call_site()), quote!(bytes_fingerprint)))
+            }
+            FnArg::Typed(arg) => match (&*arg.pat, &*arg.ty) {
+                (Pat::Ident(pat), Type::Reference(_)) => {
+                    Some((pat.ident.clone(), quote!(bytes_fingerprint)))
+                }
+                (Pat::Ident(pat), Type::Ptr(_)) => {
+                    Some((pat.ident.clone(), quote!(pointee_fingerprint)))
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .filter(|(arg, _)| !write_set.contains(&arg.to_string()))
+        .collect();
+    for (arg, fingerprint) in unlisted {
+        let message = format!(
+            "write set violated: {} modified `{arg}`, which is not listed in `modifies`",
+            fn_item.sig.ident
+        );
+        let fingerprint = quote!(::core::ub_checks::#fingerprint(#arg));
+        let pred = parse_quote!(#fingerprint == old(#fingerprint));
+        fn_item = postcondition_check(fn_item, pred, &message);
+    }
+    fn_item.into_token_stream().into()
+}
+
+/// On a type, `invariant` generates its `is_safe` method.
+///
+/// On an `impl` block of that type, it checks the invariant at the boundary of every method that
+/// is visible outside of its module: on entry for methods that take `self`, on exit for methods
+/// that take `&mut self`, and on the returned value for methods that return `Self`. The checks are
+/// enabled under the same conditions as the ones generated for `requires`. On a single method of
+/// the `impl` block, it checks the invariant at the boundary of that method only, whatever its
+/// visibility, which keeps the checks out of the methods on hot paths.
+///
+/// A method that takes `&mut self` and returns a reference to `Self` is checked through the
+/// returned reference. The exit check is skipped for the other methods whose returned value may
+/// borrow from `self`, such as `&mut [u8]` or `Cursor<'_>`, since `self` cannot be read while it is
+/// borrowed.
+pub(crate) fn invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as Item);
+    if matches!(item, Item::Impl(_) | Item::Fn(_)) && !attr.is_empty() {
+        abort_call_site!("`invariant` does not take a condition on an `impl` block or a method");
+    }
+    match item {
+        Item::Impl(item_impl) => check_invariant(item_impl).into_token_stream().into(),
+        Item::Fn(fn_item) => check_method(fn_item, "Self", None).into_token_stream().into(),
+        item => {
+            let pred = parse_macro_input!(attr as Expr);
+            type_invariant(pred, item).into()
+        }
+    }
+}
+
+/// At runtime, `loop_invariant` checks the invariant every time the head of the loop is reached:
+/// before each evaluation of the condition of a `while` loop, and at the start of each iteration
+/// of a `loop` loop.
+///
+/// A `while let` loop is turned into a `loop` that checks the invariant before matching the
+/// pattern. The checks are enabled under the same conditions as the ones generated for `requires`.
+pub(crate) fn loop_invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let pred = parse_macro_input!(attr as Expr);
+    let mut expr = crate::validate::invariant_loop(item.into());
+    let message = format!("loop invariant violated: `{}`", render(pred.to_token_stream()));
+    let check: Stmt =
+        parse_quote!(::core::ub_checks::check_contract!(check_library_ub, #message, #pred););
+    match &mut expr {
+        Expr::While(ExprWhile { attrs, label, cond, body, .. }) => {
+            if let Expr::Let(ExprLet { pat, expr: scrutinee, .. }) = &**cond {
+                let stmts = &body.stmts;
+                expr = parse_quote!(
+                    #(#attrs)*
+                    #label loop {
+                        #check
+                        let #pat = #scrutinee else { break };
+                        #(#stmts)*
+                    }
+                );
+            } else {
+                **cond = parse_quote!({
+                    #check
+                    #cond
+                });
+            }
+        }
+        Expr::Loop(ExprLoop { body, .. }) => body.stmts.insert(0, check),
+        _ => unreachable!(),
+    }
+    expr.into_token_stream().into()
+}
+
+/// At runtime, `contracts` makes each method of a trait impl check the contract it inherits from
+/// the trait: the preconditions when it is entered, and the postconditions before it returns, as
+/// `ensures` does. The provided methods of the trait are checked in the same way.
 ///
-/// TODO: At runtime the `requires` should become an assert unsafe precondition.
-pub(crate) fn requires(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    item
+/// The arguments that are given to the postconditions are clones of the ones the method received,
+/// so that they can be checked even if the body consumed them. The checks are enabled under the
+/// same conditions as the ones generated for `requires`.
+pub(crate) fn contracts(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let (provided, instrument) = (Some(check_inherited as Instrument), check_inherited);
+    crate::contracts::contracts(attr.into(), item.into(), provided, instrument).into()
 }
 
-/// For now, runtime requires is a no-op.
+fn check_inherited(fn_item: ItemFn, inherited: &Inherited) -> ItemFn {
+    let name = &inherited.name;
+    let fn_name = fn_item.sig.ident.clone();
+    let message =
+        format!("postcondition(s) violated: {fn_name} ensures the postconditions of `{name}`");
+    let result = Ident::new("result", Span::call_site());
+    let ensures = inherited.ensures(&result);
+    let pred = parse_quote!(|#result| #ensures);
+    let mut fn_item = postcondition_check(fn_item, pred, &message);
+
+    let message = format!(
+        "unsafe precondition(s) violated: {fn_name} requires the preconditions of `{name}`"
+    );
+    let requires = inherited.requires();
+    let check = parse_quote!(
+        ::core::ub_checks::check_contract!(check_library_ub, #message, #requires);
+    );
+    fn_item.block.stmts.insert(0, check);
+    fn_item
+}
+
+/// At runtime, `trusted` only records the justification. The contract of a trusted function is
+/// still checked by its `requires` and `ensures` attributes, as for any other function.
+pub(crate) fn trusted(attr: TokenStream, item: TokenStream) -> TokenStream {
+    crate::trusted::trusted(attr.into(), item.into(), None).into()
+}
+
+/// At runtime, `auto_harness` only checks that harnesses can be generated for the function, which
+/// are only used by verification tools.
+pub(crate) fn auto_harness(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AutoHarness);
+    let fn_item = parse_macro_input!(item as ItemFn);
+    harnesses(&fn_item, attr, &quote!(unreachable!()));
+    fn_item.into_token_stream().into()
+}
+
+/// At runtime, a `proof_for_contract` harness is left as is, since it is only run by
+/// verification tools, or as a test by the randomized backend.
+pub(crate) fn proof_for_contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = proc_macro2::TokenStream::from(item);
+    quote!(#[allow(dead_code)] #item).into()
+}
+
+/// At runtime, a `proof` harness is left as is too.
+pub(crate) fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
+    proof_for_contract(attr, item)
+}
+
+/// At runtime, `forall` and `exists` loop over their range until an element decides the result.
 ///
-/// TODO: At runtime the `ensures` should become an assert as well.
-pub(crate) fn ensures(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    item
+/// The loop is a plain `loop` rather than a `for` loop over the range, so that quantifiers can be
+/// used in the contracts of `const` functions.
+pub(crate) fn forall(item: TokenStream) -> TokenStream {
+    quantifier(item, true)
+}
+
+pub(crate) fn exists(item: TokenStream) -> TokenStream {
+    quantifier(item, false)
+}
+
+fn quantifier(item: TokenStream, forall: bool) -> TokenStream {
+    let Quantifier { var, start, end, pred } = parse_macro_input!(item as Quantifier);
+    let end_var = Ident::new("end", Span::mixed_site());
+    let decides = if forall { quote!(!(#pred)) } else { quote!(#pred) };
+    quote!({
+        let mut #var: usize = #start;
+        let #end_var: usize = #end;
+        loop {
+            if #var >= #end_var {
+                break #forall;
+            }
+            if #decides {
+                break !#forall;
+            }
+            #var += 1;
+        }
+    })
+    .into()
+}
+
+fn check_invariant(mut item_impl: ItemImpl) -> ItemImpl {
+    let self_ty = match &*item_impl.self_ty {
+        Type::Path(path) if path.qself.is_none() => {
+            path.path.segments.last().unwrap().ident.to_string()
+        }
+        self_ty => render(self_ty.to_token_stream()),
+    };
+    let impl_ty = (*item_impl.self_ty).clone();
+    for impl_item in &mut item_impl.items {
+        let ImplItem::Fn(method) = impl_item else { continue };
+        if matches!(method.vis, Visibility::Inherited) {
+            continue;
+        }
+        let fn_item = ItemFn {
+            attrs: std::mem::take(&mut method.attrs),
+            vis: method.vis.clone(),
+            sig: method.sig.clone(),
+            block: Box::new(method.block.clone()),
+        };
+        let fn_item = check_method(fn_item, &self_ty, Some(&impl_ty));
+        method.attrs = fn_item.attrs;
+        method.block = *fn_item.block;
+    }
+    item_impl
+}
+
+/// Check the invariant of `self_ty` at the boundary of a method of its `impl` block, whose type
+/// is `impl_ty` if it is known.
+fn check_method(mut fn_item: ItemFn, self_ty: &str, impl_ty: Option<&Type>) -> ItemFn {
+    let sig = fn_item.sig.clone();
+    let name = &sig.ident;
+    if let Some(receiver) = sig.receiver().filter(|receiver| receiver.colon_token.is_none()) {
+        let message = format!("type invariant violated: `{self_ty}` when entering `{name}`");
+        let check = parse_quote!(
+            ::core::ub_checks::check_contract!(check_library_ub, #message, self.is_safe());
+        );
+        fn_item.block.stmts.insert(0, check);
+        if receiver.mutability.is_some() && receiver.reference.is_some() {
+            let message = format!("type invariant violated: `{self_ty}` when leaving `{name}`");
+            let pred = match &sig.output {
+                ReturnType::Type(_, ty) => match &**ty {
+                    Type::Reference(reference) if is_self(&reference.elem, impl_ty) => {
+                        Some(parse_quote!(|result| result.is_safe()))
+                    }
+                    ty if borrows(ty.to_token_stream()) => None,
+                    _ => Some(parse_quote!(self.is_safe())),
+                },
+                ReturnType::Default => Some(parse_quote!(self.is_safe())),
+            };
+            if let Some(pred) = pred {
+                fn_item = postcondition_check(fn_item, pred, &message);
+            }
+        }
+    }
+    let returns_self = match &sig.output {
+        ReturnType::Type(_, ty) => is_self(ty, impl_ty),
+        ReturnType::Default => false,
+    };
+    if returns_self {
+        let message = format!("type invariant violated: `{self_ty}` returned by `{name}`");
+        fn_item = postcondition_check(fn_item, parse_quote!(|result| result.is_safe()), &message);
+    }
+    fn_item
+}
+
+/// Whether `ty` is `Self` or `impl_ty`, the type of the `impl` block.
+fn is_self(ty: &Type, impl_ty: Option<&Type>) -> bool {
+    match ty {
+        Type::Path(path) => {
+            path.path.is_ident("Self")
+                || impl_ty.is_some_and(|impl_ty| {
+                    path.to_token_stream().to_string() == impl_ty.to_token_stream().to_string()
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Whether a type may borrow from the arguments of a function, because it is a reference or has
+/// a lifetime parameter.
+fn borrows(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Punct(punct) => matches!(punct.as_char(), '&' | '\''),
+        TokenTree::Group(group) => borrows(group.stream()),
+        TokenTree::Ident(_) | TokenTree::Literal(_) => false,
+    })
+}
+
+/// Wrap the body of `fn_item` so that `pred` is checked on the value it returns.
+fn postcondition_check(mut fn_item: ItemFn, pred: Expr, message: &str) -> ItemFn {
+    let (binding, mut check) = match pred {
+        Expr::Closure(closure) if closure.inputs.len() == 1 => {
+            (closure.inputs.into_iter().next().unwrap(), *closure.body)
+        }
+        pred => (parse_quote!(_), pred),
+    };
+
+    let mut old = ReplaceOld(Vec::new());
+    old.visit_expr_mut(&mut check);
+    let names: Vec<_> =
+        (0..old.0.len()).map(|idx| Ident::new(&format!("old_{idx}"), Span::mixed_site())).collect();
+    let snapshots = old.0.iter().map(|expr| {
+        if fn_item.sig.constness.is_some() {
+            expr.to_token_stream()
+        } else {
+            quote!(::core::clone::Clone::clone(&#expr))
+        }
+    });
+    if !names.is_empty() {
+        check = parse_quote!(match (#(&#names,)*) {
+            (#(::core::option::Option::Some(#names),)*) => #check,
+            _ => true,
+        });
+    }
+
+    let result = Ident::new("result", Span::mixed_site());
+    let label = Lifetime::new("'contract", Span::mixed_site());
+    let ty = match &fn_item.sig.output {
+        ReturnType::Default => Some(quote!(())),
+        ReturnType::Type(_, ty) if !idents(ty.to_token_stream()).contains(&"impl".to_string()) => {
+            Some(ty.to_token_stream())
+        }
+        ReturnType::Type(..) => None,
+    }
+    .map(|ty| quote!(: #ty));
+    let mut body = fn_item.block;
+    ReturnToBreak(&label).visit_block_mut(&mut body);
+    // The snapshots are taken after the preconditions were checked, which is where they are
+    // inserted if `requires` is applied before `ensures`.
+    let mut stmts = body.stmts;
+    let count = stmts.iter().take_while(|stmt| is_check(stmt)).count();
+    let pre_checks: Vec<_> = stmts.drain(..count).collect();
+    fn_item.block = parse_quote!({
+        #(#pre_checks)*
+        #(let #names = ::core::ub_checks::contract_snapshot!(check_library_ub, #snapshots);)*
+        let #result #ty = #label: { #(#stmts)* };
+        ::core::ub_checks::check_contract!(check_library_ub, #message, {
+            let #binding = &#result;
+            #check
+        });
+        #result
+    });
+    fn_item
+}
+
+/// Replace each `old(expr)` in a postcondition by a reference to the value of `expr` that was
+/// captured when entering the function, and collect those expressions.
+///
+/// The `n`th expression is bound to `old_n`, which is a reference inside the postcondition.
+struct ReplaceOld(Vec<Expr>);
+
+impl VisitMut for ReplaceOld {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+        if let Expr::Call(call) = expr {
+            if matches!(&*call.func, Expr::Path(path) if path.path.is_ident("old"))
+                && call.args.len() == 1
+            {
+                let name = Ident::new(&format!("old_{}", self.0.len()), Span::mixed_site());
+                self.0.push(call.args.pop().unwrap().into_value());
+                *expr = parse_quote!((*#name));
+            }
+        }
+    }
+}
+
+/// Whether this statement is a contract check that was inserted at the beginning of the body
+/// by the runtime lowering of `requires`.
+fn is_check(stmt: &Stmt) -> bool {
+    let Stmt::Macro(stmt) = stmt else { return false };
+    let name = stmt.mac.path.segments.last().map(|segment| segment.ident.to_string());
+    matches!(name.as_deref(), Some("assert_unsafe_precondition" | "check_contract"))
+}
+
+/// Replace the `return` expressions of a function body by a `break` out of the labeled block
+/// that wraps the body, so that the returned value can be checked.
+struct ReturnToBreak<'a>(&'a Lifetime);
+
+impl VisitMut for ReturnToBreak<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            // A `return` in those belongs to the closure or the async block.
+            Expr::Closure(_) | Expr::Async(_) => {}
+            Expr::Return(ret) => {
+                visit_mut::visit_expr_return_mut(self, ret);
+                let label = self.0;
+                let value = ret.expr.take();
+                *expr = parse_quote!(break #label #value);
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {
+        // Nested items have their own body.
+    }
+}
+
+/// Build the statement that checks `pred` at the beginning of a function with signature `sig`.
+///
+/// The arguments referenced by the contract are captured by value, and the function generics are
+/// redeclared on the check function. Generic parameters of an enclosing `impl` block are not
+/// visible from here, so contracts that mention `self` or `Self` are checked inline instead, as
+/// are contracts that refer to an argument whose type is not known to be `Copy`, since the check
+/// function would move it.
+fn precondition_check(sig: &Signature, pred: &Expr) -> Stmt {
+    let message = format!("{} requires `{}`", sig.ident, render(pred.to_token_stream()));
+    let used = idents(pred.to_token_stream());
+    let args: Vec<_> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) if used.contains(&pat.ident.to_string()) => {
+                    Some((&pat.ident, &arg.ty))
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let captures_self = used.iter().any(|ident| ident == "self" || ident == "Self")
+        || args.iter().any(|(_, ty)| {
+            idents(ty.to_token_stream()).iter().any(|ident| ident == "Self" || ident == "impl")
+        });
+    if captures_self || !args.iter().all(|(_, ty)| is_copy(ty)) {
+        let message = format!("unsafe precondition(s) violated: {message}");
+        return parse_quote!(
+            ::core::ub_checks::check_contract!(check_library_ub, #message, #pred);
+        );
+    }
+
+    let generics = sig.generics.params.iter().map(|param| match param {
+        GenericParam::Type(ty) => {
+            let (ident, bounds) = (&ty.ident, &ty.bounds);
+            quote!(#ident: #bounds)
+        }
+        GenericParam::Const(cnst) => {
+            let (ident, ty) = (&cnst.ident, &cnst.ty);
+            quote!(const #ident: #ty)
+        }
+        GenericParam::Lifetime(lt) => lt.to_token_stream(),
+    });
+    let instances = sig.generics.params.iter().filter_map(|param| match param {
+        GenericParam::Type(ty) => Some(&ty.ident),
+        GenericParam::Const(cnst) => Some(&cnst.ident),
+        GenericParam::Lifetime(_) => None,
+    });
+    let bounds = sig.generics.where_clause.as_ref().map(|clause| &clause.predicates);
+    let names = args.iter().map(|(name, _)| name);
+    let types = args.iter().map(|(_, ty)| ty);
+    let values = args.iter().map(|(name, _)| name);
+    parse_quote!(
+        ::core::ub_checks::assert_unsafe_precondition!(
+            check_library_ub,
+            #message,
+            [#(#generics),*] [#(#instances),*] where [#bounds],
+            (#(#names: #types = #values),*) => #pred
+        );
+    )
+}
+
+/// Whether `ty` is syntactically known to be `Copy`: a primitive type, a raw pointer, a shared
+/// reference, or an array or a tuple of those.
+fn is_copy(ty: &Type) -> bool {
+    match ty {
+        Type::Ptr(_) | Type::Never(_) => true,
+        Type::Reference(reference) => reference.mutability.is_none(),
+        Type::Array(array) => is_copy(&array.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().all(is_copy),
+        Type::Paren(paren) => is_copy(&paren.elem),
+        Type::Group(group) => is_copy(&group.elem),
+        Type::Path(path) if path.qself.is_none() => path.path.get_ident().is_some_and(|ident| {
+            matches!(
+                ident.to_string().as_str(),
+                "bool"
+                    | "char"
+                    | "u8"
+                    | "u16"
+                    | "u32"
+                    | "u64"
+                    | "u128"
+                    | "usize"
+                    | "i8"
+                    | "i16"
+                    | "i32"
+                    | "i64"
+                    | "i128"
+                    | "isize"
+                    | "f16"
+                    | "f32"
+                    | "f64"
+                    | "f128"
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// Collect every identifier in the given tokens, including the ones in nested groups.
+fn idents(tokens: proc_macro2::TokenStream) -> Vec<String> {
+    let mut result = Vec::new();
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => result.push(ident.to_string()),
+            TokenTree::Group(group) => result.extend(idents(group.stream())),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compare the expansion with the expected function, which is parsed first so that both are
+    /// printed the same way.
+    fn assert_expands_to(fn_item: ItemFn, expected: proc_macro2::TokenStream) {
+        let expected: ItemFn = syn::parse2(expected).unwrap();
+        assert_eq!(
+            fn_item.into_token_stream().to_string(),
+            expected.into_token_stream().to_string()
+        );
+    }
+
+    #[test]
+    fn old_clones_non_copy_argument() {
+        let fn_item = parse_quote!(
+            fn append(v: &mut Vec<u8>, extra: Vec<u8>) {
+                v.extend(extra);
+            }
+        );
+        let pred = parse_quote!(v.len() >= old(extra).len());
+        assert_expands_to(
+            postcondition_check(fn_item, pred, "append ensures"),
+            quote!(
+                fn append(v: &mut Vec<u8>, extra: Vec<u8>) {
+                    let old_0 = ::core::ub_checks::contract_snapshot!(
+                        check_library_ub,
+                        ::core::clone::Clone::clone(&extra)
+                    );
+                    let result: () = 'contract: {
+                        v.extend(extra);
+                    };
+                    ::core::ub_checks::check_contract!(check_library_ub, "append ensures", {
+                        let _ = &result;
+                        match (&old_0,) {
+                            (::core::option::Option::Some(old_0),) => v.len() >= (*old_0).len(),
+                            _ => true,
+                        }
+                    });
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn old_copies_in_const_fn() {
+        let fn_item = parse_quote!(
+            const fn double(x: &mut u32) {
+                *x *= 2;
+            }
+        );
+        let pred = parse_quote!(*x == old(*x) * 2);
+        assert_expands_to(
+            postcondition_check(fn_item, pred, "double ensures"),
+            quote!(
+                const fn double(x: &mut u32) {
+                    let old_0 = ::core::ub_checks::contract_snapshot!(check_library_ub, *x);
+                    let result: () = 'contract: {
+                        *x *= 2;
+                    };
+                    ::core::ub_checks::check_contract!(check_library_ub, "double ensures", {
+                        let _ = &result;
+                        match (&old_0,) {
+                            (::core::option::Option::Some(old_0),) => *x == (*old_0) * 2,
+                            _ => true,
+                        }
+                    });
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn early_return_breaks_out_of_labeled_block() {
+        let fn_item = parse_quote!(
+            fn first(v: &[u8]) -> Option<u8> {
+                if v.is_empty() {
+                    return None;
+                }
+                let f = || {
+                    return 0;
+                };
+                Some(v[0] + f())
+            }
+        );
+        let pred = parse_quote!(|result| result.is_some() != v.is_empty());
+        assert_expands_to(
+            postcondition_check(fn_item, pred, "first ensures"),
+            quote!(
+                fn first(v: &[u8]) -> Option<u8> {
+                    let result: Option<u8> = 'contract: {
+                        if v.is_empty() {
+                            break 'contract None;
+                        }
+                        let f = || {
+                            return 0;
+                        };
+                        Some(v[0] + f())
+                    };
+                    ::core::ub_checks::check_contract!(check_library_ub, "first ensures", {
+                        let result = &result;
+                        result.is_some() != v.is_empty()
+                    });
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn snapshots_follow_preconditions() {
+        let mut fn_item: ItemFn = parse_quote!(
+            unsafe fn read(ptr: *const u8) -> u8 {
+                *ptr
+            }
+        );
+        let pred = parse_quote!(!ptr.is_null());
+        fn_item.block.stmts.insert(0, precondition_check(&fn_item.sig, &pred));
+        let pred = parse_quote!(|result| *result == old(*ptr));
+        assert_expands_to(
+            postcondition_check(fn_item, pred, "read ensures"),
+            quote!(
+                unsafe fn read(ptr: *const u8) -> u8 {
+                    ::core::ub_checks::assert_unsafe_precondition!(
+                        check_library_ub,
+                        "read requires `!ptr.is_null()`",
+                        [] [] where [],
+                        (ptr: *const u8 = ptr) => !ptr.is_null()
+                    );
+                    let old_0 = ::core::ub_checks::contract_snapshot!(
+                        check_library_ub,
+                        ::core::clone::Clone::clone(&*ptr)
+                    );
+                    let result: u8 = 'contract: { *ptr };
+                    ::core::ub_checks::check_contract!(check_library_ub, "read ensures", {
+                        let result = &result;
+                        match (&old_0,) {
+                            (::core::option::Option::Some(old_0),) => *result == (*old_0),
+                            _ => true,
+                        }
+                    });
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn invariant_of_borrowing_methods() {
+        let item_impl = parse_quote!(
+            impl<'data> Buf<'data> {
+                pub fn clear(&mut self) -> &mut Self {
+                    self.filled = 0;
+                    self
+                }
+                pub fn filled_mut(&mut self) -> &mut [u8] {
+                    &mut self.buf[..self.filled]
+                }
+                pub fn unfilled<'this>(&'this mut self) -> Cursor<'this> {
+                    Cursor { buf: self }
+                }
+            }
+        );
+        assert_eq!(
+            check_invariant(item_impl).into_token_stream().to_string(),
+            syn::parse2::<ItemImpl>(quote!(
+                impl<'data> Buf<'data> {
+                    pub fn clear(&mut self) -> &mut Self {
+                        ::core::ub_checks::check_contract!(
+                            check_library_ub,
+                            "type invariant violated: `Buf` when entering `clear`",
+                            self.is_safe()
+                        );
+                        let result: &mut Self = 'contract: {
+                            self.filled = 0;
+                            self
+                        };
+                        ::core::ub_checks::check_contract!(
+                            check_library_ub,
+                            "type invariant violated: `Buf` when leaving `clear`",
+                            {
+                                let result = &result;
+                                result.is_safe()
+                            }
+                        );
+                        result
+                    }
+                    pub fn filled_mut(&mut self) -> &mut [u8] {
+                        ::core::ub_checks::check_contract!(
+                            check_library_ub,
+                            "type invariant violated: `Buf` when entering `filled_mut`",
+                            self.is_safe()
+                        );
+                        &mut self.buf[..self.filled]
+                    }
+                    pub fn unfilled<'this>(&'this mut self) -> Cursor<'this> {
+                        ::core::ub_checks::check_contract!(
+                            check_library_ub,
+                            "type invariant violated: `Buf` when entering `unfilled`",
+                            self.is_safe()
+                        );
+                        Cursor { buf: self }
+                    }
+                }
+            ))
+            .unwrap()
+            .into_token_stream()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn invariant_of_a_single_constructor() {
+        let fn_item = parse_quote!(
+            unsafe fn from_raw_parts_in(ptr: *mut T, capacity: usize, alloc: A) -> Self {
+                Self { ptr, cap: capacity, alloc }
+            }
+        );
+        assert_expands_to(
+            check_method(fn_item, "Self", None),
+            quote!(
+                unsafe fn from_raw_parts_in(ptr: *mut T, capacity: usize, alloc: A) -> Self {
+                    let result: Self = 'contract: { Self { ptr, cap: capacity, alloc } };
+                    ::core::ub_checks::check_contract!(
+                        check_library_ub,
+                        "type invariant violated: `Self` returned by `from_raw_parts_in`",
+                        {
+                            let result = &result;
+                            result.is_safe()
+                        }
+                    );
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn precondition_on_non_copy_argument_is_inline() {
+        let sig: Signature = parse_quote!(fn take(v: Vec<u8>, len: usize));
+        let pred = parse_quote!(v.len() == len);
+        let check = precondition_check(&sig, &pred);
+        assert_eq!(
+            check.into_token_stream().to_string(),
+            quote!(::core::ub_checks::check_contract!(
+                check_library_ub,
+                "unsafe precondition(s) violated: take requires `v.len() == len`",
+                v.len() == len
+            );)
+            .to_string(),
+        );
+    }
 }
@@ -0,0 +1,52 @@
+//! Tool independent part of the `trusted` attribute, which marks the functions whose contract is
+//! assumed rather than verified, such as intrinsics that tools can only model.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, abort_call_site};
+use quote::ToTokens;
+use syn::{Expr, ExprLit, ItemFn, Lit, LitStr, Meta, MetaNameValue};
+
+use crate::doc::document_text;
+
+/// Lowers the body of a trusted function so that its contract is assumed.
+pub(crate) type Stub = fn(ItemFn) -> ItemFn;
+
+/// Check the justification of a trusted function, add it to the documentation of the function,
+/// and lower the function with `stub`, if any.
+///
+/// No item is added next to the function, since it could land in a public namespace or in a
+/// public impl. Audit tools find the justification in the `trusted` attribute of the sources, or
+/// in the documentation of the compiled crate.
+pub(crate) fn trusted(attr: TokenStream, item: TokenStream, stub: Option<Stub>) -> TokenStream {
+    let reason = reason(attr);
+    let mut fn_item: ItemFn = syn::parse2(item).unwrap_or_else(|err| abort!(err.span(), err));
+    document_text(&mut fn_item.attrs, "Trusted", &reason.value());
+    if let Some(stub) = stub {
+        fn_item = stub(fn_item);
+    }
+    fn_item.into_token_stream()
+}
+
+/// Parse the `reason = "..."` argument, which is mandatory and cannot be empty.
+pub(crate) fn reason(attr: TokenStream) -> LitStr {
+    if attr.is_empty() {
+        abort_call_site!(
+            "`trusted` requires a justification";
+            help = "write `#[safety::trusted(reason = \"...\")]`"
+        );
+    }
+    let meta: Meta = syn::parse2(attr).unwrap_or_else(|err| abort!(err.span(), err));
+    match meta {
+        Meta::NameValue(MetaNameValue {
+            path,
+            value: Expr::Lit(ExprLit { lit: Lit::Str(reason), .. }),
+            ..
+        }) if path.is_ident("reason") => {
+            if reason.value().trim().is_empty() {
+                abort!(reason, "the justification of a trusted function cannot be empty");
+            }
+            reason
+        }
+        meta => abort!(meta, "expected `reason = \"...\"`"),
+    }
+}
@@ -0,0 +1,118 @@
+//! Tool independent derive of the `ValidBits` trait of `core::ub_checks`.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{parse_quote, Attribute, Data, DeriveInput, Fields, Ident, Index, Member};
+
+/// Generate the `ValidBits` impl of a type, which checks the bytes of each field of a struct, or
+/// the discriminant of a fieldless enum.
+///
+/// The layout of the struct must be defined, so that the impl checks the bytes a transmutation
+/// would actually put in each field, and the enum must have an integer `repr` to read the
+/// discriminant from. The type parameters of the type are required to implement `ValidBits` too.
+pub(crate) fn derive(input: DeriveInput) -> TokenStream {
+    let reprs = reprs(&input.attrs);
+    let body = match &input.data {
+        Data::Struct(data) => {
+            if !reprs.iter().any(|repr| repr == "C" || repr == "transparent") {
+                abort!(
+                    input.ident,
+                    "`ValidBits` can only be derived for `repr(C)` or `repr(transparent)` structs"
+                );
+            }
+            if data.fields.is_empty() {
+                quote!(
+                    let _ = ptr;
+                    true
+                )
+            } else {
+                let fields = data.fields.iter().enumerate().map(|(index, field)| {
+                    let member = match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(Index::from(index)),
+                    };
+                    let ty = &field.ty;
+                    quote!(
+                        <#ty as ::core::ub_checks::ValidBits>::is_valid_bits(
+                            ptr.add(::core::mem::offset_of!(Self, #member)),
+                        )
+                    )
+                });
+                quote!(
+                    // SAFETY: the fields are in the bytes of `Self`, which the caller guarantees to
+                    // be readable and initialized.
+                    unsafe { #(#fields)&&* }
+                )
+            }
+        }
+        Data::Enum(data) => {
+            let Some(repr) = reprs.iter().find(|repr| is_int(repr)) else {
+                abort!(
+                    input.ident,
+                    "`ValidBits` can only be derived for enums with an integer `repr`"
+                )
+            };
+            let variants = data.variants.iter().map(|variant| {
+                if !matches!(variant.fields, Fields::Unit) {
+                    abort!(variant, "`ValidBits` can only be derived for fieldless enums");
+                }
+                let ident = &variant.ident;
+                quote!(tag == Self::#ident as #repr)
+            });
+            quote!(
+                // SAFETY: the discriminant is the first field of `Self`, which the caller
+                // guarantees to be readable and initialized.
+                let tag = unsafe { ptr.cast::<#repr>().read_unaligned() };
+                false #(|| #variants)*
+            )
+        }
+        Data::Union(_) => abort!(input.ident, "`ValidBits` cannot be derived for unions"),
+    };
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::core::ub_checks::ValidBits));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote!(
+        // SAFETY: `Self` is valid if each of its fields is, or if its discriminant is the one of a
+        // variant.
+        unsafe impl #impl_generics ::core::ub_checks::ValidBits for #ident #ty_generics
+            #where_clause
+        {
+            #[inline]
+            unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+                #body
+            }
+        }
+    )
+}
+
+/// The representations listed in the `repr` attributes of the type.
+fn reprs(attrs: &[Attribute]) -> Vec<Ident> {
+    let mut reprs = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                reprs.push(ident.clone());
+            }
+            // Skip the arguments of `align(N)` and `packed(N)`.
+            if meta.input.peek(syn::token::Paren) {
+                let _ = meta.input.parse::<proc_macro2::Group>();
+            }
+            Ok(())
+        });
+        if let Err(err) = parsed {
+            abort!(attr, err);
+        }
+    }
+    reprs
+}
+
+fn is_int(repr: &Ident) -> bool {
+    ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]
+        .iter()
+        .any(|int| repr == int)
+}
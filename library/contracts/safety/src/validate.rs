@@ -0,0 +1,244 @@
+//! Checks of the contract expressions that are the same for every tool, so that mistakes are
+//! reported at the contract instead of deep inside of the code that the tool generates.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, emit_error};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    BinOp, Block, Expr, ExprAssign, ExprBinary, ExprCall, ExprClosure, ExprForLoop, ExprIf,
+    ExprLet, ExprPath, ExprReference, FnArg, Ident, Item, Macro, Pat, Signature, Stmt, Token,
+};
+
+use crate::quantifier::Quantifier;
+
+/// Parse the predicate of a `requires` or `ensures` attribute of the function with signature
+/// `sig`, and report the identifiers it cannot refer to and the expressions that mutate state.
+///
+/// The predicate can refer to the parameters of the function and to the variables that it binds
+/// itself. A postcondition can also use `old(expr)`, and the value returned by the function
+/// through the parameter of a closure. The other identifiers are only accepted as the function of
+/// a call, or if they start with an uppercase letter, since they are then items rather than
+/// variables.
+pub(crate) fn contract(attr: TokenStream, sig: &Signature, is_ensures: bool) -> Expr {
+    let pred: Expr = syn::parse2(attr).unwrap_or_else(|err| abort!(err.span(), err));
+    let params = sig
+        .inputs
+        .iter()
+        .flat_map(|input| match input {
+            FnArg::Receiver(receiver) => vec![Ident::new("self", receiver.self_token.span)],
+            FnArg::Typed(pat_type) => bindings(&pat_type.pat),
+        })
+        .collect();
+    let mut checker =
+        ScopeChecker { function: sig.ident.to_string(), is_ensures, scopes: vec![params] };
+    match &pred {
+        // The parameters of the closure of a postcondition are bound to the returned value.
+        Expr::Closure(closure) if is_ensures => checker.visit_closure(closure),
+        pred => checker.visit_expr(pred),
+    }
+    pred
+}
+
+/// Parse the loop that a `loop_invariant` attribute is applied to, which must be a `while` or a
+/// `loop` loop, since Kani only verifies the invariants of those.
+pub(crate) fn invariant_loop(item: TokenStream) -> Expr {
+    let expr: Expr = syn::parse2(item).unwrap_or_else(|err| abort!(err.span(), err));
+    match &expr {
+        Expr::While(_) | Expr::Loop(_) => expr,
+        Expr::ForLoop(ExprForLoop { for_token, .. }) => abort!(
+            for_token,
+            "`loop_invariant` cannot be applied to a `for` loop";
+            help = "Kani only verifies the invariants of `while` and `loop` loops, rewrite it as a \
+                    `while` loop"
+        ),
+        _ => abort!(expr, "`loop_invariant` can only be applied to a loop"),
+    }
+}
+
+/// Reports the variables of a contract that are not in scope, and the mutations.
+struct ScopeChecker {
+    function: String,
+    is_ensures: bool,
+    /// The variables in scope, innermost last.
+    scopes: Vec<Vec<Ident>>,
+}
+
+impl ScopeChecker {
+    fn in_scope(&self, ident: &Ident) -> bool {
+        self.scopes.iter().flatten().any(|var| var == ident)
+    }
+
+    fn bind(&mut self, pat: &Pat) {
+        self.scopes.last_mut().unwrap().extend(bindings(pat));
+    }
+
+    fn scoped(&mut self, vars: Vec<Ident>, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(vars);
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn visit_closure(&mut self, closure: &ExprClosure) {
+        let vars = closure.inputs.iter().flat_map(bindings).collect();
+        self.scoped(vars, |this| this.visit_expr(&closure.body));
+    }
+}
+
+impl<'ast> Visit<'ast> for ScopeChecker {
+    fn visit_expr_path(&mut self, path: &'ast ExprPath) {
+        if let Some(ident) = path.path.get_ident() {
+            let is_variable = ident.to_string().starts_with(|c: char| c.is_lowercase() || c == '_');
+            if is_variable && !self.in_scope(ident) {
+                emit_error!(
+                    ident,
+                    "cannot find `{}` in the parameters of `{}`", ident, self.function;
+                    help = "a contract can only refer to the parameters of the function"
+                );
+            }
+        }
+        visit::visit_expr_path(self, path);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        match &*call.func {
+            Expr::Path(func) if func.path.is_ident("old") && !self.is_ensures => {
+                emit_error!(func, "`old` can only be used in `ensures`");
+            }
+            // A function, rather than a variable.
+            Expr::Path(func) if func.path.get_ident().is_some() => {}
+            func => self.visit_expr(func),
+        }
+        for arg in &call.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_closure(&mut self, closure: &'ast ExprClosure) {
+        self.visit_closure(closure);
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        self.scoped(Vec::new(), |this| {
+            for stmt in &block.stmts {
+                match stmt {
+                    Stmt::Local(local) => {
+                        if let Some(init) = &local.init {
+                            this.visit_expr(&init.expr);
+                            if let Some((_, diverge)) = &init.diverge {
+                                this.visit_expr(diverge);
+                            }
+                        }
+                        this.bind(&local.pat);
+                    }
+                    stmt => this.visit_stmt(stmt),
+                }
+            }
+        });
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast ExprIf) {
+        // The variables bound by `if let` are only in scope in the first branch.
+        self.scoped(Vec::new(), |this| {
+            this.visit_expr(&expr_if.cond);
+            this.visit_block(&expr_if.then_branch);
+        });
+        if let Some((_, else_branch)) = &expr_if.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_let(&mut self, expr_let: &'ast ExprLet) {
+        self.visit_expr(&expr_let.expr);
+        self.bind(&expr_let.pat);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        self.scoped(bindings(&arm.pat), |this| {
+            if let Some((_, guard)) = &arm.guard {
+                this.visit_expr(guard);
+            }
+            this.visit_expr(&arm.body);
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+        self.scoped(bindings(&for_loop.pat), |this| this.visit_block(&for_loop.body));
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        let name = mac.path.segments.last().map(|segment| segment.ident.to_string());
+        if matches!(name.as_deref(), Some("forall" | "exists")) {
+            // Errors in the quantifier itself are reported when it is expanded.
+            if let Ok(quantifier) = mac.parse_body::<Quantifier>() {
+                self.visit_expr(&quantifier.start);
+                self.visit_expr(&quantifier.end);
+                self.scoped(vec![quantifier.var], |this| this.visit_expr(&quantifier.pred));
+            }
+        } else if let Ok(args) =
+            mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        {
+            // The arguments of the macros that do not take expressions, such as the pattern of
+            // `matches!`, are not checked.
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast ExprAssign) {
+        emit_error!(assign.eq_token, "a contract cannot assign");
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast ExprBinary) {
+        if matches!(
+            binary.op,
+            BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+                | BinOp::RemAssign(_)
+                | BinOp::BitXorAssign(_)
+                | BinOp::BitAndAssign(_)
+                | BinOp::BitOrAssign(_)
+                | BinOp::ShlAssign(_)
+                | BinOp::ShrAssign(_)
+        ) {
+            emit_error!(binary.op, "a contract cannot assign");
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_expr_reference(&mut self, reference: &'ast ExprReference) {
+        if let Some(mutability) = reference.mutability {
+            emit_error!(mutability, "a contract cannot borrow mutably");
+        }
+        visit::visit_expr_reference(self, reference);
+    }
+
+    fn visit_item(&mut self, _: &'ast Item) {
+        // Nested items are not part of the contract.
+    }
+}
+
+/// The variables that are bound by a pattern.
+fn bindings(pat: &Pat) -> Vec<Ident> {
+    struct Bindings(Vec<Ident>);
+
+    impl<'ast> Visit<'ast> for Bindings {
+        fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+            self.0.push(pat.ident.clone());
+            visit::visit_pat_ident(self, pat);
+        }
+
+        fn visit_expr(&mut self, _: &'ast Expr) {
+            // Constants and ranges in patterns do not bind anything.
+        }
+    }
+
+    let mut bindings = Bindings(Vec::new());
+    bindings.visit_pat(pat);
+    bindings.0
+}
@@ -0,0 +1,167 @@
+//! Run harnesses with the randomized backend, against a stand-in for the parts of `core` that the
+//! generated code uses, since the library itself can only be tested by its own build.
+//!
+//! ```text
+//! RUSTFLAGS="--cfg safety_random" cargo test
+//! ```
+
+#![cfg(all(safety_random, not(kani_host)))]
+
+extern crate self as core;
+
+pub use std::{any, assert, concat, marker, module_path, panic, stringify};
+
+pub mod ub_checks {
+    #[macro_export]
+    macro_rules! check_contract {
+        ($kind:ident, $message:expr, $e:expr $(,)?) => {
+            if ::core::ub_checks::$kind() && !$e {
+                ::std::panic!("{}", $message);
+            }
+        };
+    }
+    pub use check_contract;
+
+    pub fn check_library_ub() -> bool {
+        true
+    }
+}
+
+/// The subset of `core::kani` that the harnesses below use, with a simpler generator.
+pub mod kani {
+    use std::cell::Cell;
+
+    pub use safety::{proof, proof_for_contract, Arbitrary};
+
+    const REJECTED: &str = "assumption does not hold, the input is rejected";
+
+    thread_local! {
+        static STATE: Cell<u64> = const { Cell::new(1) };
+        static TARGET: Cell<&'static str> = const { Cell::new("") };
+    }
+
+    pub fn seed(seed: u64) {
+        STATE.set(seed | 1);
+        TARGET.set("");
+    }
+
+    pub fn harness_seed(name: &str, iteration: u64) -> u64 {
+        name.bytes().fold(iteration, |hash, byte| hash.wrapping_mul(31) ^ byte as u64)
+    }
+
+    pub fn target(function: &'static str) {
+        TARGET.set(function);
+    }
+
+    pub fn is_rejection(message: &str) -> bool {
+        let target = TARGET.get();
+        message == REJECTED
+            || (!target.is_empty()
+                && message
+                    .starts_with(&format!("unsafe precondition(s) violated: {target} requires ")))
+    }
+
+    pub fn assume(cond: bool) {
+        if !cond {
+            std::panic!("{REJECTED}");
+        }
+    }
+
+    pub trait Arbitrary {
+        fn any() -> Self;
+    }
+
+    pub fn any<T: Arbitrary>() -> T {
+        T::any()
+    }
+
+    fn next() -> u64 {
+        let mut x = STATE.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        STATE.set(x);
+        x
+    }
+
+    impl Arbitrary for u8 {
+        fn any() -> Self {
+            next() as u8
+        }
+    }
+
+    impl Arbitrary for usize {
+        fn any() -> Self {
+            next() as usize
+        }
+    }
+}
+
+#[safety::requires(divisor != 0)]
+#[safety::ensures(|quotient| *quotient <= dividend)]
+fn divide(dividend: u8, divisor: u8) -> u8 {
+    dividend / divisor
+}
+
+#[safety::ensures(|result| *result != 3)]
+fn low_bits(value: u8) -> u8 {
+    value % 4
+}
+
+struct Counter(u8);
+
+impl Counter {
+    #[safety::requires(self.0 > 0)]
+    fn decrement(&mut self) {
+        self.0 -= 1;
+    }
+}
+
+mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[derive(kani::Arbitrary)]
+    enum Operand {
+        Zero,
+        Value(u8),
+    }
+
+    // The inputs with a zero divisor are rejected by the precondition of `divide`.
+    #[kani::proof_for_contract(divide)]
+    fn check_divide() {
+        let _ = divide(kani::any(), kani::any());
+    }
+
+    #[kani::proof_for_contract(<Counter>::decrement)]
+    fn check_decrement() {
+        Counter(kani::any()).decrement();
+    }
+
+    #[kani::proof]
+    #[kani::unwind(3)]
+    fn check_operand() {
+        let operand: Operand = kani::any();
+        kani::assume(!matches!(operand, Operand::Zero));
+        let Operand::Value(value) = operand else { unreachable!() };
+        assert_eq!(divide(value, 1), value);
+    }
+
+    #[kani::proof_for_contract(low_bits)]
+    #[should_panic(expected = "replay with SAFETY_SEED")]
+    fn check_low_bits() {
+        let _ = low_bits(kani::any());
+    }
+}
+
+/// A harness of a crate without unit tests, which is a function that records its target.
+fn check_divide_by_zero() {
+    kani::target("divide");
+    let _ = divide(kani::any(), 0);
+}
+
+#[test]
+#[should_panic(expected = "rejected all of its")]
+fn run_harness() {
+    safety::run_harness!("random::check_divide_by_zero", check_divide_by_zero);
+}
@@ -117,6 +117,7 @@ use crate::ptr;
 ///   could be detected via an allocator that tracks allocations by printing or otherwise
 ///   having side effects.
 #[stable(feature = "global_alloc", since = "1.28.0")]
+#[safety::contracts]
 pub unsafe trait GlobalAlloc {
     /// Allocate memory as described by the given `layout`.
     ///
@@ -151,6 +152,8 @@ pub unsafe trait GlobalAlloc {
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
     #[stable(feature = "global_alloc", since = "1.28.0")]
+    #[safety::requires(layout.size() != 0)]
+    #[safety::ensures(|result| result.is_null() || result.is_aligned_to(layout.align()))]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8;
 
     /// Deallocate the block of memory at the given `ptr` pointer with the given `layout`.
@@ -166,6 +169,7 @@ pub unsafe trait GlobalAlloc {
     /// * `layout` must be the same layout that was used
     ///   to allocate that block of memory.
     #[stable(feature = "global_alloc", since = "1.28.0")]
+    #[safety::requires(!ptr.is_null() && layout.size() != 0)]
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
 
     /// Behaves like `alloc`, but also ensures that the contents
@@ -188,6 +192,8 @@ pub unsafe trait GlobalAlloc {
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
     #[stable(feature = "global_alloc", since = "1.28.0")]
+    #[safety::requires(layout.size() != 0)]
+    #[safety::ensures(|result| result.is_null() || result.is_aligned_to(layout.align()))]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
         let size = layout.size();
         // SAFETY: the safety contract for `alloc` must be upheld by the caller.
@@ -257,6 +263,9 @@ pub unsafe trait GlobalAlloc {
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
     #[stable(feature = "global_alloc", since = "1.28.0")]
+    #[safety::requires(!ptr.is_null() && layout.size() != 0 && new_size != 0)]
+    #[safety::requires(Layout::from_size_align(new_size, layout.align()).is_ok())]
+    #[safety::ensures(|result| result.is_null() || result.is_aligned_to(layout.align()))]
     unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
         // SAFETY: the caller must ensure that the `new_size` does not overflow.
         // `layout.align()` comes from a `Layout` and is thus guaranteed to be valid.
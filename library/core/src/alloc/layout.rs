@@ -7,6 +7,8 @@
 use crate::cmp;
 use crate::error::Error;
 use crate::fmt;
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 use crate::mem;
 use crate::ptr::{Alignment, NonNull};
 
@@ -37,6 +39,7 @@ const fn size_align<T>() -> (usize, usize) {
 #[stable(feature = "alloc_layout", since = "1.28.0")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[lang = "alloc_layout"]
+#[safety::invariant(self.size <= Self::max_size_for_align(self.align))]
 pub struct Layout {
     // size of the requested block of memory, measured in bytes.
     size: usize,
@@ -51,6 +54,7 @@ pub struct Layout {
     align: Alignment,
 }
 
+#[safety::invariant]
 impl Layout {
     /// Constructs a `Layout` from a given `size` and `align`,
     /// or returns `LayoutError` if any of the following conditions
@@ -67,6 +71,11 @@ impl Layout {
     #[rustc_const_stable(feature = "const_alloc_layout_size_align", since = "1.50.0")]
     #[inline]
     #[rustc_allow_const_fn_unstable(ptr_alignment_type)]
+    #[safety::ensures(|result| result.is_err() || align.is_power_of_two())]
+    #[safety::ensures(|result| match result {
+        Ok(layout) => layout.size() == size && layout.align() == align,
+        Err(_) => true,
+    })]
     pub const fn from_size_align(size: usize, align: usize) -> Result<Self, LayoutError> {
         if !align.is_power_of_two() {
             return Err(LayoutError);
@@ -492,3 +501,30 @@ impl fmt::Display for LayoutError {
         f.write_str("invalid parameters to Layout::from_size_align")
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for Layout {
+    fn any() -> Self {
+        let align: Alignment = kani::any();
+        let size = kani::any_where(|size: &usize| *size <= Self::max_size_for_align(align));
+        // The invariant holds by construction, so that harnesses do not need to assume it.
+        Layout { size, align }
+    }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature="kani", issue="none")]
+pub(crate) mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[kani::proof_for_contract(Layout::from_size_align)]
+    pub fn check_from_size_align() {
+        let size = kani::any::<usize>();
+        let align = kani::any::<usize>();
+        if let Ok(layout) = Layout::from_size_align(size, align) {
+            assert!(layout.is_safe());
+        }
+    }
+}
@@ -2,8 +2,8 @@
 
 #![stable(feature = "alloc_module", since = "1.28.0")]
 
-mod global;
-mod layout;
+pub(crate) mod global;
+pub(crate) mod layout;
 
 #[stable(feature = "global_alloc", since = "1.28.0")]
 pub use self::global::GlobalAlloc;
@@ -108,6 +108,7 @@ impl fmt::Display for AllocError {
 ///
 /// [*currently allocated*]: #currently-allocated-memory
 #[unstable(feature = "allocator_api", issue = "32838")]
+#[safety::contracts]
 pub unsafe trait Allocator {
     /// Attempts to allocate a block of memory.
     ///
@@ -133,6 +134,7 @@ pub unsafe trait Allocator {
     /// call the [`handle_alloc_error`] function, rather than directly invoking `panic!` or similar.
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
+    #[safety::ensures(|result| result.map_or(true, |block| block.len() >= layout.size()))]
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
 
     /// Behaves like `allocate`, but also ensures that the returned memory is zero-initialized.
@@ -150,6 +152,7 @@ pub unsafe trait Allocator {
     /// call the [`handle_alloc_error`] function, rather than directly invoking `panic!` or similar.
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
+    #[safety::ensures(|result| result.map_or(true, |block| block.len() >= layout.size()))]
     fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
         let ptr = self.allocate(layout)?;
         // SAFETY: `alloc` returns a valid memory block
@@ -206,6 +209,8 @@ pub unsafe trait Allocator {
     /// call the [`handle_alloc_error`] function, rather than directly invoking `panic!` or similar.
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
+    #[safety::requires(new_layout.size() >= old_layout.size())]
+    #[safety::ensures(|result| result.map_or(true, |block| block.len() >= new_layout.size()))]
     unsafe fn grow(
         &self,
         ptr: NonNull<u8>,
@@ -269,6 +274,8 @@ pub unsafe trait Allocator {
     /// call the [`handle_alloc_error`] function, rather than directly invoking `panic!` or similar.
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
+    #[safety::requires(new_layout.size() >= old_layout.size())]
+    #[safety::ensures(|result| result.map_or(true, |block| block.len() >= new_layout.size()))]
     unsafe fn grow_zeroed(
         &self,
         ptr: NonNull<u8>,
@@ -333,6 +340,8 @@ pub unsafe trait Allocator {
     /// call the [`handle_alloc_error`] function, rather than directly invoking `panic!` or similar.
     ///
     /// [`handle_alloc_error`]: ../../alloc/alloc/fn.handle_alloc_error.html
+    #[safety::requires(new_layout.size() <= old_layout.size())]
+    #[safety::ensures(|result| result.map_or(true, |block| block.len() >= new_layout.size()))]
     unsafe fn shrink(
         &self,
         ptr: NonNull<u8>,
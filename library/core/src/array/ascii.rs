@@ -38,6 +38,7 @@ impl<const N: usize> [u8; N] {
     #[unstable(feature = "ascii_char", issue = "110998")]
     #[must_use]
     #[inline]
+    #[safety::requires(safety::forall!(|i in 0..N| self[i].is_ascii()))]
     pub const unsafe fn as_ascii_unchecked(&self) -> &[ascii::Char; N] {
         let byte_ptr: *const [u8; N] = self;
         let ascii_ptr = byte_ptr as *const [ascii::Char; N];
@@ -3,9 +3,8 @@
 use crate::num::NonZero;
 use crate::{
     fmt,
-    intrinsics::transmute_unchecked,
     iter::{self, FusedIterator, TrustedLen, TrustedRandomAccessNoCoerce},
-    mem::MaybeUninit,
+    mem::{transmute_unchecked, MaybeUninit},
     ops::{IndexRange, Range},
     ptr,
 };
@@ -4,6 +4,8 @@
 //! helps with clarity as we're also referring to `char` intentionally in here.
 
 use crate::fmt::{self, Write};
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 use crate::mem::transmute;
 
 /// One of the 128 Unicode characters from U+0000 through U+007F,
@@ -466,6 +468,7 @@ impl AsciiChar {
     /// `b` must be in `0..=127`, or else this is UB.
     #[unstable(feature = "ascii_char", issue = "110998")]
     #[inline]
+    #[safety::requires(unsafe { crate::ub_checks::is_valid_bits::<AsciiChar>(&b) })]
     pub const unsafe fn from_u8_unchecked(b: u8) -> Self {
         // SAFETY: Our safety precondition is that `b` is in-range.
         unsafe { transmute(b) }
@@ -616,3 +619,13 @@ impl fmt::Debug for AsciiChar {
         f.write_char('\'')
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for AsciiChar {
+    fn any() -> Self {
+        let b = kani::any_where(|b: &u8| *b <= 127);
+        // SAFETY: just checked that `b` is in-range.
+        unsafe { Self::from_u8_unchecked(b) }
+    }
+}
@@ -226,6 +226,9 @@ impl char {
     #[rustc_const_unstable(feature = "const_char_from_u32_unchecked", issue = "89259")]
     #[must_use]
     #[inline]
+    #[safety::requires(unsafe {
+        crate::ub_checks::is_valid_bits::<char>(&i as *const u32 as *const u8)
+    })]
     pub const unsafe fn from_u32_unchecked(i: u32) -> char {
         // SAFETY: the safety contract must be upheld by the caller.
         unsafe { super::convert::from_u32_unchecked(i) }
@@ -29,6 +29,8 @@ mod bytewise;
 pub(crate) use bytewise::BytewiseEq;
 
 use self::Ordering::*;
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 
 /// Trait for comparisons using the equality operator.
 ///
@@ -375,6 +377,7 @@ pub struct AssertParamIsEq<T: Eq + ?Sized> {
 /// assert_eq!(2.cmp(&1), Ordering::Greater);
 /// ```
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(crate::ub_checks::ValidBits)]
 #[stable(feature = "rust1", since = "1.0.0")]
 // This is a lang item only so that `BinOp::Cmp` in MIR can return it.
 // It has no special behaviour, but does require that the three variants
@@ -1791,3 +1794,11 @@ mod impls {
         }
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for Ordering {
+    fn any() -> Self {
+        kani::any::<i8>().cmp(&0)
+    }
+}
@@ -63,7 +63,7 @@
 )]
 #![allow(missing_docs)]
 
-use safety::requires;
+use safety::{auto_harness, modifies, requires};
 use crate::marker::DiscriminantKind;
 use crate::marker::Tuple;
 use crate::mem::align_of;
@@ -2713,8 +2713,9 @@ pub const fn is_val_statically_known<T: Copy>(_arg: T) -> bool {
 #[rustc_intrinsic]
 // This has fallback `const fn` MIR, so shouldn't need stability, see #122652
 #[rustc_const_unstable(feature = "const_typed_swap", issue = "none")]
-#[cfg_attr(kani, kani::modifies(x))]
-#[cfg_attr(kani, kani::modifies(y))]
+#[auto_harness(types = [(), u16, u128, bool, crate::num::NonZeroU64])]
+#[modifies(x)]
+#[modifies(y)]
 #[requires(ub_checks::can_dereference(x) && ub_checks::can_write(x))]
 #[requires(ub_checks::can_dereference(y) && ub_checks::can_write(y))]
 #[requires(x.addr() != y.addr() || core::mem::size_of::<T>() == 0)]
@@ -2927,6 +2928,9 @@ impl<P: ?Sized, T: ptr::Thin> AggregateRawPtr<*mut T> for *mut P {
 #[inline(always)]
 #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
 #[rustc_diagnostic_item = "ptr_copy_nonoverlapping"]
+#[requires(ub_checks::can_read_slice(src as *const crate::mem::MaybeUninit<T>, count))]
+#[requires(ub_checks::can_write_slice(dst, count))]
+#[requires(ub_checks::nonoverlapping(src, dst, count))]
 pub const unsafe fn copy_nonoverlapping<T>(src: *const T, dst: *mut T, count: usize) {
     extern "rust-intrinsic" {
         #[rustc_const_unstable(feature = "const_intrinsic_copy", issue = "80697")]
@@ -3029,6 +3033,8 @@ pub const unsafe fn copy_nonoverlapping<T>(src: *const T, dst: *mut T, count: us
 #[inline(always)]
 #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
 #[rustc_diagnostic_item = "ptr_copy"]
+#[requires(ub_checks::can_read_slice(src as *const crate::mem::MaybeUninit<T>, count))]
+#[requires(ub_checks::can_write_slice(dst, count))]
 pub const unsafe fn copy<T>(src: *const T, dst: *mut T, count: usize) {
     extern "rust-intrinsic" {
         #[rustc_const_unstable(feature = "const_intrinsic_copy", issue = "80697")]
@@ -3110,6 +3116,7 @@ pub const unsafe fn copy<T>(src: *const T, dst: *mut T, count: usize) {
 #[inline(always)]
 #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
 #[rustc_diagnostic_item = "ptr_write_bytes"]
+#[requires(ub_checks::can_write_slice(dst, count))]
 pub const unsafe fn write_bytes<T>(dst: *mut T, val: u8, count: usize) {
     extern "rust-intrinsic" {
         #[rustc_const_unstable(feature = "const_ptr_write", issue = "86302")]
@@ -3153,9 +3160,9 @@ pub(crate) const fn miri_promise_symbolic_alignment(ptr: *const (), align: usize
     const_eval_select((ptr, align), compiletime, runtime);
 }
 
-#[cfg(kani)]
+#[cfg(any(kani, safety_random))]
 #[unstable(feature="kani", issue="none")]
-mod verify {
+pub(crate) mod verify {
     use core::{cmp, fmt};
     use super::*;
     use crate::kani;
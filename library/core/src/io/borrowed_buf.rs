@@ -25,6 +25,7 @@ use crate::{cmp, ptr};
 /// write-only iterator).
 ///
 /// The lifetime `'data` is a bound on the lifetime of the underlying data.
+#[safety::invariant(self.filled <= self.init && self.init <= self.buf.len())]
 pub struct BorrowedBuf<'data> {
     /// The buffer's underlying data.
     buf: &'data mut [MaybeUninit<u8>],
@@ -69,6 +70,7 @@ impl<'data> From<&'data mut [MaybeUninit<u8>]> for BorrowedBuf<'data> {
     }
 }
 
+#[safety::invariant]
 impl<'data> BorrowedBuf<'data> {
     /// Returns the total capacity of the buffer.
     #[inline]
@@ -139,6 +141,9 @@ impl<'data> BorrowedBuf<'data> {
     ///
     /// The caller must ensure that the first `n` unfilled bytes of the buffer have already been initialized.
     #[inline]
+    #[safety::requires(
+        n <= self.capacity() && crate::ub_checks::is_initialized(self.buf.as_ptr() as *const u8, n)
+    )]
     pub unsafe fn set_init(&mut self, n: usize) -> &mut Self {
         self.init = cmp::max(self.init, n);
         self
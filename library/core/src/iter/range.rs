@@ -22,6 +22,7 @@ unsafe_impl_trusted_step![AsciiChar char i8 i16 i32 i64 i128 isize u8 u16 u32 u6
 /// The *successor* operation moves towards values that compare greater.
 /// The *predecessor* operation moves towards values that compare lesser.
 #[unstable(feature = "step_trait", issue = "42168")]
+#[safety::contracts]
 pub trait Step: Clone + PartialOrd + Sized {
     /// Returns the number of *successor* steps required to get from `start` to `end`.
     ///
@@ -107,6 +108,7 @@ pub trait Step: Clone + PartialOrd + Sized {
     /// For any `a` and `n`, where no overflow occurs:
     ///
     /// * `Step::forward_unchecked(a, n)` is equivalent to `Step::forward(a, n)`
+    #[safety::requires(Step::forward_checked(start, count).is_some())]
     unsafe fn forward_unchecked(start: Self, count: usize) -> Self {
         Step::forward(start, count)
     }
@@ -177,6 +179,7 @@ pub trait Step: Clone + PartialOrd + Sized {
     /// For any `a` and `n`, where no overflow occurs:
     ///
     /// * `Step::backward_unchecked(a, n)` is equivalent to `Step::backward(a, n)`
+    #[safety::requires(Step::backward_checked(start, count).is_some())]
     unsafe fn backward_unchecked(start: Self, count: usize) -> Self {
         Step::backward(start, count)
     }
@@ -187,12 +190,14 @@ pub trait Step: Clone + PartialOrd + Sized {
 macro_rules! step_signed_methods {
     ($unsigned: ty) => {
         #[inline]
+        #[safety::contracts(Step)]
         unsafe fn forward_unchecked(start: Self, n: usize) -> Self {
             // SAFETY: the caller has to guarantee that `start + n` doesn't overflow.
             unsafe { start.checked_add_unsigned(n as $unsigned).unwrap_unchecked() }
         }
 
         #[inline]
+        #[safety::contracts(Step)]
         unsafe fn backward_unchecked(start: Self, n: usize) -> Self {
             // SAFETY: the caller has to guarantee that `start - n` doesn't overflow.
             unsafe { start.checked_sub_unsigned(n as $unsigned).unwrap_unchecked() }
@@ -203,12 +208,14 @@ macro_rules! step_signed_methods {
 macro_rules! step_unsigned_methods {
     () => {
         #[inline]
+        #[safety::contracts(Step)]
         unsafe fn forward_unchecked(start: Self, n: usize) -> Self {
             // SAFETY: the caller has to guarantee that `start + n` doesn't overflow.
             unsafe { start.unchecked_add(n as Self) }
         }
 
         #[inline]
+        #[safety::contracts(Step)]
         unsafe fn backward_unchecked(start: Self, n: usize) -> Self {
             // SAFETY: the caller has to guarantee that `start - n` doesn't overflow.
             unsafe { start.unchecked_sub(n as Self) }
@@ -432,6 +439,7 @@ step_integer_impls! {
 }
 
 #[unstable(feature = "step_trait", reason = "recently redesigned", issue = "42168")]
+#[safety::contracts]
 impl Step for char {
     #[inline]
     fn steps_between(&start: &char, &end: &char) -> Option<usize> {
@@ -511,6 +519,7 @@ impl Step for char {
 }
 
 #[unstable(feature = "step_trait", reason = "recently redesigned", issue = "42168")]
+#[safety::contracts]
 impl Step for AsciiChar {
     #[inline]
     fn steps_between(&start: &AsciiChar, &end: &AsciiChar) -> Option<usize> {
@@ -553,6 +562,7 @@ impl Step for AsciiChar {
 }
 
 #[unstable(feature = "step_trait", reason = "recently redesigned", issue = "42168")]
+#[safety::contracts]
 impl Step for Ipv4Addr {
     #[inline]
     fn steps_between(&start: &Ipv4Addr, &end: &Ipv4Addr) -> Option<usize> {
@@ -585,6 +595,7 @@ impl Step for Ipv4Addr {
 }
 
 #[unstable(feature = "step_trait", reason = "recently redesigned", issue = "42168")]
+#[safety::contracts]
 impl Step for Ipv6Addr {
     #[inline]
     fn steps_between(&start: &Ipv6Addr, &end: &Ipv6Addr) -> Option<usize> {
@@ -1392,3 +1403,52 @@ unsafe impl<A: TrustedStep> TrustedLen for ops::RangeInclusive<A> {}
 
 #[stable(feature = "fused", since = "1.26.0")]
 impl<A: Step> FusedIterator for ops::RangeInclusive<A> {}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+pub(crate) mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[kani::proof_for_contract(<char as Step>::forward_unchecked)]
+    pub fn check_forward_unchecked_char() {
+        let start: char = kani::any();
+        let count: usize = kani::any();
+        let _ = unsafe { Step::forward_unchecked(start, count) };
+    }
+
+    #[kani::proof_for_contract(<char as Step>::backward_unchecked)]
+    pub fn check_backward_unchecked_char() {
+        let start: char = kani::any();
+        let count: usize = kani::any();
+        let _ = unsafe { Step::backward_unchecked(start, count) };
+    }
+
+    #[kani::proof_for_contract(<Ipv4Addr as Step>::forward_unchecked)]
+    pub fn check_forward_unchecked_ipv4() {
+        let start: Ipv4Addr = kani::any();
+        let count: usize = kani::any();
+        let _ = unsafe { Step::forward_unchecked(start, count) };
+    }
+
+    #[kani::proof_for_contract(<Ipv4Addr as Step>::backward_unchecked)]
+    pub fn check_backward_unchecked_ipv4() {
+        let start: Ipv4Addr = kani::any();
+        let count: usize = kani::any();
+        let _ = unsafe { Step::backward_unchecked(start, count) };
+    }
+
+    #[kani::proof_for_contract(<u8 as Step>::forward_unchecked)]
+    pub fn check_forward_unchecked_u8() {
+        let start: u8 = kani::any();
+        let count: usize = kani::any();
+        let _ = unsafe { Step::forward_unchecked(start, count) };
+    }
+
+    #[kani::proof_for_contract(<i8 as Step>::backward_unchecked)]
+    pub fn check_backward_unchecked_i8() {
+        let start: i8 = kani::any();
+        let count: usize = kani::any();
+        let _ = unsafe { Step::backward_unchecked(start, count) };
+    }
+}
@@ -0,0 +1,271 @@
+//! A stand-in for the `kani` module under the randomized backend of the `safety` contracts, which
+//! is selected with `--cfg safety_random` and runs the harnesses with pseudo-random inputs.
+//!
+//! The harnesses can use the same subset of the Kani API as when they are verified, except that
+//! [`any`] draws a value from a deterministic pseudo-random generator instead of a symbolic one,
+//! and that [`assume`] rejects the input of the test if its condition does not hold. A harness
+//! records the function it verifies with [`target`], and its runner calls [`seed`] before each
+//! input and [`is_rejection`] to tell rejected inputs from failures.
+//!
+//! Since `core` has no unit tests, its harnesses stay functions, which are listed in
+//! [`HARNESSES`] for its integration tests to run them with `safety::run_harness`.
+
+use crate::cell::Cell;
+use crate::num::NonZero;
+
+pub use safety::{proof, proof_for_contract, Arbitrary};
+
+/// A harness, with the path of its function that the seeds of its inputs are derived from.
+pub type Harness = (&'static str, fn());
+
+/// The harnesses at the given paths in `core`, each with its full path.
+macro_rules! harnesses {
+    ($($($segment:ident)::+),* $(,)?) => {
+        &[$((
+            concat!("core", $("::", stringify!($segment)),+),
+            crate::$($segment)::+ as fn(),
+        )),*]
+    };
+}
+
+/// The harnesses of `core`, by module. They are listed by hand, since they are not unit tests,
+/// except the ones that `auto_harness` generates, which their module lists. The
+/// `harnesses_are_listed` test of `core` fails if a harness of a `verify` module is missing.
+pub static HARNESSES: &[&[Harness]] = &[
+    harnesses![alloc::layout::verify::check_from_size_align],
+    harnesses![
+        intrinsics::verify::check_typed_swap_u8,
+        intrinsics::verify::check_typed_swap_char,
+        intrinsics::verify::check_typed_swap_non_zero,
+    ],
+    crate::intrinsics::__safety_harnesses_typed_swap::HARNESSES,
+    harnesses![
+        iter::range::verify::check_forward_unchecked_char,
+        iter::range::verify::check_backward_unchecked_char,
+        iter::range::verify::check_forward_unchecked_ipv4,
+        iter::range::verify::check_backward_unchecked_ipv4,
+        iter::range::verify::check_forward_unchecked_u8,
+        iter::range::verify::check_backward_unchecked_i8,
+    ],
+    harnesses![
+        mem::verify::check_swap_primitive,
+        mem::verify::check_swap_adt_no_drop,
+        mem::verify::check_transmute_unchecked_non_zero,
+        mem::verify::check_transmute_unchecked_char,
+    ],
+    crate::mem::__safety_harnesses_swap::HARNESSES,
+    harnesses![ptr::verify::check_read_u128],
+    harnesses![ptr::alignment::verify::check_new],
+    harnesses![ptr::metadata::verify::check_size_of],
+    harnesses![slice::cmp::verify::check_compare_bytes, slice::cmp::verify::check_cmp_u8],
+    harnesses![
+        slice::index::verify::check_get_unchecked_usize,
+        slice::index::verify::check_get_unchecked_mut_range,
+    ],
+];
+
+/// The message of the panic of [`assume`], which rejects the input of the test.
+const REJECTED: &str = "assumption does not hold, the input is rejected";
+
+/// The initial state of the generator, which is the one of the `DeterministicRng` of the tests of
+/// `alloc`.
+const INITIAL: [u32; 4] = [0x193a6754, 0xa8a7d469, 0x97830e05, 0x113ba7bb];
+
+/// The state of the xorshift generator of the current thread, since tests run in parallel.
+#[thread_local]
+static STATE: Cell<[u32; 4]> = Cell::new(INITIAL);
+
+/// The name of the function that the running harness verifies, or an empty name if it does not
+/// verify a contract.
+#[thread_local]
+static TARGET: Cell<&'static str> = Cell::new("");
+
+/// Start drawing the values of an input from the given seed.
+pub fn seed(seed: u64) {
+    let [x, y, z, w] = INITIAL;
+    let (low, high) = (seed as u32, (seed >> 32) as u32);
+    let state = [x ^ low, y ^ high, z ^ low.rotate_left(16), w ^ high.rotate_left(16)];
+    // The generator never leaves the state where every word is zero.
+    STATE.set(if state == [0; 4] { INITIAL } else { state });
+    TARGET.set("");
+}
+
+/// Record that the running harness verifies the function with the given name, whose violated
+/// preconditions reject the input instead of failing the test.
+pub fn target(function: &'static str) {
+    TARGET.set(function);
+}
+
+/// The seed of the `iteration`th input of the harness with the given path, which only depends on
+/// them so that every run of the test draws the same inputs.
+pub fn harness_seed(name: &str, iteration: u64) -> u64 {
+    // The FNV-1a hash of the path, mixed with the iteration by the finalizer of SplitMix64.
+    let mut hash = 0xcbf29ce484222325_u64;
+    for byte in name.bytes() {
+        hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+    }
+    let mut z = hash ^ iteration.wrapping_mul(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Whether a panic with the given message rejects the input of the running harness, which is the
+/// case if it comes from [`assume`] or from a precondition of its [`target`].
+///
+/// The precondition is recognized by the name of the function in the message, so the violation of
+/// a precondition of another function of the same name, as in a recursive call, rejects the input
+/// instead of failing the test.
+pub fn is_rejection(message: &str) -> bool {
+    if message == REJECTED {
+        return true;
+    }
+    let Some(message) = message.strip_prefix("unsafe precondition(s) violated: ") else {
+        return false;
+    };
+    let target = TARGET.get();
+    !target.is_empty()
+        && message.strip_prefix(target).is_some_and(|message| message.starts_with(" requires "))
+}
+
+fn next_u32() -> u32 {
+    let [x, y, z, w] = STATE.get();
+    let t = x ^ (x << 11);
+    let next = w ^ (w >> 19) ^ (t ^ (t >> 8));
+    STATE.set([y, z, w, next]);
+    next
+}
+
+fn next_u128() -> u128 {
+    (0..4).fold(0, |bits, _| (bits << 32) | next_u32() as u128)
+}
+
+/// Assume that `cond` holds, by rejecting the input of the test otherwise.
+#[track_caller]
+pub fn assume(cond: bool) {
+    if !cond {
+        crate::panicking::panic(REJECTED);
+    }
+}
+
+/// Check that `cond` holds, failing the test with `msg` otherwise.
+#[track_caller]
+pub fn assert(cond: bool, msg: &'static str) {
+    if !cond {
+        crate::panicking::panic(msg);
+    }
+}
+
+/// A type whose values can be drawn by a harness.
+pub trait Arbitrary: Sized {
+    fn any() -> Self;
+}
+
+/// Draw a value of type `T`.
+pub fn any<T: Arbitrary>() -> T {
+    T::any()
+}
+
+/// Draw a value of type `T` that satisfies `f`, rejecting the input otherwise.
+pub fn any_where<T: Arbitrary, F: FnOnce(&T) -> bool>(f: F) -> T {
+    let value = T::any();
+    assume(f(&value));
+    value
+}
+
+/// The bits of an integer. Small values and all ones, which is the maximum of unsigned types and
+/// -1 for signed ones, are drawn more often than the others, since they are the ones that
+/// preconditions and edge cases are usually about.
+fn int_bits() -> u128 {
+    match next_u32() % 8 {
+        0 => (next_u32() % 16) as u128,
+        1 => u128::MAX,
+        _ => next_u128(),
+    }
+}
+
+macro_rules! int_arbitrary {
+    ($($ty:ty),*) => {$(
+        impl Arbitrary for $ty {
+            fn any() -> Self {
+                int_bits() as $ty
+            }
+        }
+
+        impl Arbitrary for NonZero<$ty> {
+            fn any() -> Self {
+                loop {
+                    if let Some(value) = NonZero::new(<$ty>::any()) {
+                        break value;
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+int_arbitrary!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Arbitrary for bool {
+    fn any() -> Self {
+        next_u32() & 1 == 1
+    }
+}
+
+impl Arbitrary for char {
+    fn any() -> Self {
+        loop {
+            if let Some(c) = char::from_u32(next_u32() % 0x110000) {
+                break c;
+            }
+        }
+    }
+}
+
+impl Arbitrary for f32 {
+    fn any() -> Self {
+        f32::from_bits(next_u32())
+    }
+}
+
+impl Arbitrary for f64 {
+    fn any() -> Self {
+        f64::from_bits(next_u128() as u64)
+    }
+}
+
+impl<T: Arbitrary, const N: usize> Arbitrary for [T; N] {
+    fn any() -> Self {
+        crate::array::from_fn(|_| T::any())
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn any() -> Self {
+        if bool::any() { Some(T::any()) } else { None }
+    }
+}
+
+impl<T: Arbitrary, E: Arbitrary> Arbitrary for Result<T, E> {
+    fn any() -> Self {
+        if bool::any() { Ok(T::any()) } else { Err(E::any()) }
+    }
+}
+
+macro_rules! tuple_arbitrary {
+    ($($name:ident)*) => {
+        impl<$($name: Arbitrary),*> Arbitrary for ($($name,)*) {
+            fn any() -> Self {
+                ($($name::any(),)*)
+            }
+        }
+    };
+}
+
+tuple_arbitrary!();
+tuple_arbitrary!(A);
+tuple_arbitrary!(A B);
+tuple_arbitrary!(A B C);
+tuple_arbitrary!(A B C D);
+tuple_arbitrary!(A B C D E);
+tuple_arbitrary!(A B C D E F);
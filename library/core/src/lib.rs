@@ -244,6 +244,7 @@
 #![feature(no_core)]
 #![feature(no_sanitize)]
 #![feature(prelude_import)]
+#![feature(proc_macro_hygiene)]
 #![feature(repr_simd)]
 #![feature(rustc_allow_const_fn_unstable)]
 #![feature(rustc_attrs)]
@@ -275,6 +276,8 @@
 #![feature(tbm_target_feature)]
 #![feature(wasm_target_feature)]
 // tidy-alphabetical-end
+// The `kani` module of the randomized backend of the contracts has a state per thread.
+#![cfg_attr(safety_random, feature(thread_local))]
 
 // allow using `core::` in intra-doc links
 #[allow(unused_extern_crates)]
@@ -434,6 +437,10 @@ pub mod primitive;
 #[cfg(kani)]
 kani_core::kani_lib!(core);
 
+#[cfg(all(safety_random, not(kani)))]
+#[unstable(feature = "kani", issue = "none")]
+pub mod kani;
+
 // Pull in the `core_arch` crate directly into core. The contents of
 // `core_arch` are in a different repository: rust-lang/stdarch.
 //
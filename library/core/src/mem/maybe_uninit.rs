@@ -624,6 +624,8 @@ impl<T> MaybeUninit<T> {
     #[inline(always)]
     #[rustc_diagnostic_item = "assume_init"]
     #[track_caller]
+    #[rustc_allow_const_fn_unstable(const_ub_checks)]
+    #[safety::requires(crate::ub_checks::is_initialized(self.as_ptr(), 1))]
     pub const unsafe fn assume_init(self) -> T {
         // SAFETY: the caller must guarantee that `self` is initialized.
         // This also means that `self` must be a `value` variant.
@@ -788,6 +790,8 @@ impl<T> MaybeUninit<T> {
     #[stable(feature = "maybe_uninit_ref", since = "1.55.0")]
     #[rustc_const_stable(feature = "const_maybe_uninit_assume_init_ref", since = "1.59.0")]
     #[inline(always)]
+    #[rustc_allow_const_fn_unstable(const_ub_checks)]
+    #[safety::requires(crate::ub_checks::is_initialized(self.as_ptr(), 1))]
     pub const unsafe fn assume_init_ref(&self) -> &T {
         // SAFETY: the caller must guarantee that `self` is initialized.
         // This also means that `self` must be a `value` variant.
@@ -944,6 +948,7 @@ impl<T> MaybeUninit<T> {
     #[rustc_const_unstable(feature = "const_maybe_uninit_array_assume_init", issue = "96097")]
     #[inline(always)]
     #[track_caller]
+    #[safety::requires(crate::ub_checks::is_initialized(array.as_ptr() as *const T, N))]
     pub const unsafe fn array_assume_init<const N: usize>(array: [Self; N]) -> [T; N] {
         // SAFETY:
         // * The caller guarantees that all elements of the array are initialized
@@ -952,7 +957,9 @@ impl<T> MaybeUninit<T> {
         // And thus the conversion is safe
         unsafe {
             intrinsics::assert_inhabited::<[T; N]>();
-            intrinsics::transmute_unchecked(array)
+            // The elements can be of any type, so their validity is not checked beyond the
+            // contract of this function.
+            mem::transmute_unchecked_unvalidated(array)
         }
     }
 
@@ -970,6 +977,7 @@ impl<T> MaybeUninit<T> {
     #[unstable(feature = "maybe_uninit_slice", issue = "63569")]
     #[rustc_const_unstable(feature = "maybe_uninit_slice", issue = "63569")]
     #[inline(always)]
+    #[safety::requires(crate::ub_checks::is_initialized(slice.as_ptr() as *const T, slice.len()))]
     pub const unsafe fn slice_assume_init_ref(slice: &[Self]) -> &[T] {
         // SAFETY: casting `slice` to a `*const [T]` is safe since the caller guarantees that
         // `slice` is initialized, and `MaybeUninit` is guaranteed to have the same layout as `T`.
@@ -1421,7 +1429,7 @@ impl<T, const N: usize> MaybeUninit<[T; N]> {
     #[inline]
     pub const fn transpose(self) -> [MaybeUninit<T>; N] {
         // SAFETY: T and MaybeUninit<T> have the same layout
-        unsafe { intrinsics::transmute_unchecked(self) }
+        unsafe { mem::transmute_unchecked(self) }
     }
 }
 
@@ -1441,7 +1449,7 @@ impl<T, const N: usize> [MaybeUninit<T>; N] {
     #[inline]
     pub const fn transpose(self) -> MaybeUninit<[T; N]> {
         // SAFETY: T and MaybeUninit<T> have the same layout
-        unsafe { intrinsics::transmute_unchecked(self) }
+        unsafe { mem::transmute_unchecked(self) }
     }
 }
 
@@ -32,6 +32,47 @@ pub use transmutability::{Assume, BikeshedIntrinsicFrom};
 #[doc(inline)]
 pub use crate::intrinsics::transmute;
 
+/// Calls [`intrinsics::transmute_unchecked`], which the callers in `core` use to reinterpret
+/// values whose types only have the same size once monomorphized, with its safety contract.
+///
+/// The bit validity of `src` is checked with [`ValidBits`], so `Dst` must implement it. The
+/// callers whose `Dst` is a generic type that cannot be required to implement it use
+/// [`transmute_unchecked_unvalidated`] instead.
+///
+/// [`ValidBits`]: crate::ub_checks::ValidBits
+///
+/// # Safety
+///
+/// `Src` and `Dst` must have the same size, and the bytes of `src` must be a valid `Dst`.
+#[inline(always)]
+#[rustc_allow_const_fn_unstable(const_ub_checks)]
+// The bytes of `src` are only read once their size is known to be the one of `Dst`.
+#[safety::requires(size_of::<Src>() == size_of::<Dst>()
+    && unsafe { crate::ub_checks::is_valid_bits::<Dst>(&src as *const Src as *const u8) })]
+pub(crate) const unsafe fn transmute_unchecked<Src, Dst: crate::ub_checks::ValidBits>(
+    src: Src,
+) -> Dst {
+    // SAFETY: the caller must uphold the safety contract for `transmute_unchecked`.
+    unsafe { intrinsics::transmute_unchecked(src) }
+}
+
+/// Calls [`intrinsics::transmute_unchecked`] like [`transmute_unchecked`], but only checks that
+/// `Src` and `Dst` have the same size, for a `Dst` that does not implement [`ValidBits`].
+///
+/// The bit validity of `src` is not checked, so each caller must explain why it holds.
+///
+/// [`ValidBits`]: crate::ub_checks::ValidBits
+///
+/// # Safety
+///
+/// `Src` and `Dst` must have the same size, and the bytes of `src` must be a valid `Dst`.
+#[inline(always)]
+#[safety::requires(size_of::<Src>() == size_of::<Dst>())]
+pub(crate) const unsafe fn transmute_unchecked_unvalidated<Src, Dst>(src: Src) -> Dst {
+    // SAFETY: the caller must uphold the safety contract for `transmute_unchecked_unvalidated`.
+    unsafe { intrinsics::transmute_unchecked(src) }
+}
+
 /// Takes ownership and "forgets" about the value **without running its destructor**.
 ///
 /// Any resources the value manages, such as heap memory or a file handle, will linger
@@ -728,8 +769,9 @@ pub unsafe fn uninitialized<T>() -> T {
 #[stable(feature = "rust1", since = "1.0.0")]
 #[rustc_const_unstable(feature = "const_swap", issue = "83163")]
 #[rustc_diagnostic_item = "mem_swap"]
-#[cfg_attr(kani, crate::kani::modifies(x))]
-#[cfg_attr(kani, crate::kani::modifies(y))]
+#[safety::auto_harness(types = [u64, (), [u16; 3]])]
+#[safety::modifies(x)]
+#[safety::modifies(y)]
 pub const fn swap<T>(x: &mut T, y: &mut T) {
     // SAFETY: `&mut` guarantees these are typed readable and writable
     // as well as non-overlapping.
@@ -1351,9 +1393,9 @@ pub macro offset_of($Container:ty, $($fields:expr)+ $(,)?) {
     {builtin # offset_of($Container, $($fields)+)}
 }
 
-#[cfg(kani)]
+#[cfg(any(kani, safety_random))]
 #[unstable(feature="kani", issue="none")]
-mod verify {
+pub(crate) mod verify {
     use super::*;
     use crate::kani;
 
@@ -1384,4 +1426,16 @@ mod verify {
         forget(x);
         forget(y);
     }
+
+    #[kani::proof_for_contract(transmute_unchecked)]
+    pub fn check_transmute_unchecked_non_zero() {
+        let src: u32 = kani::any();
+        let _: crate::num::NonZero<u32> = unsafe { transmute_unchecked(src) };
+    }
+
+    #[kani::proof_for_contract(transmute_unchecked)]
+    pub fn check_transmute_unchecked_char() {
+        let src: u32 = kani::any();
+        let _: char = unsafe { transmute_unchecked(src) };
+    }
 }
@@ -1,6 +1,8 @@
 use crate::cmp::Ordering;
 use crate::fmt::{self, Write};
 use crate::iter;
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 use crate::mem::transmute;
 use crate::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
 
@@ -2332,3 +2334,27 @@ bitop_impls! {
     #[stable(feature = "ip_bitops", since = "1.75.0")]
     impl (BitOr, BitOrAssign) for Ipv6Addr = (bitor, bitor_assign);
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for IpAddr {
+    fn any() -> Self {
+        if kani::any() { IpAddr::V4(kani::any()) } else { IpAddr::V6(kani::any()) }
+    }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for Ipv4Addr {
+    fn any() -> Self {
+        Ipv4Addr { octets: kani::any() }
+    }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for Ipv6Addr {
+    fn any() -> Self {
+        Ipv6Addr { octets: kani::any() }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::fmt::{self, Write};
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 use crate::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use super::display_buffer::DisplayBuffer;
@@ -642,3 +644,32 @@ impl fmt::Debug for SocketAddrV6 {
         fmt::Display::fmt(self, fmt)
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for SocketAddr {
+    fn any() -> Self {
+        if kani::any() { SocketAddr::V4(kani::any()) } else { SocketAddr::V6(kani::any()) }
+    }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for SocketAddrV4 {
+    fn any() -> Self {
+        SocketAddrV4 { ip: kani::any(), port: kani::any() }
+    }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for SocketAddrV6 {
+    fn any() -> Self {
+        SocketAddrV6 {
+            ip: kani::any(),
+            port: kani::any(),
+            flowinfo: kani::any(),
+            scope_id: kani::any(),
+        }
+    }
+}
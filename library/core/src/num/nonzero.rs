@@ -4,6 +4,7 @@ use crate::cmp::Ordering;
 use crate::fmt;
 use crate::hash::{Hash, Hasher};
 use crate::intrinsics;
+use crate::mem;
 use crate::marker::{Freeze, StructuralPartialEq};
 use crate::ops::{BitOr, BitOrAssign, Div, DivAssign, Neg, Rem, RemAssign};
 use crate::panic::{RefUnwindSafe, UnwindSafe};
@@ -357,7 +358,8 @@ where
     pub const fn new(n: T) -> Option<Self> {
         // SAFETY: Memory layout optimization guarantees that `Option<NonZero<T>>` has
         //         the same layout and size as `T`, with `0` representing `None`.
-        unsafe { intrinsics::transmute_unchecked(n) }
+        //         Any bits of `T` are therefore a valid `Option<Self>`.
+        unsafe { mem::transmute_unchecked_unvalidated(n) }
     }
 
     /// Creates a non-zero without checking whether the value is non-zero.
@@ -443,8 +445,8 @@ where
         // For now, using the transmute `assume`s the range at runtime.
         //
         // SAFETY: `ZeroablePrimitive` guarantees that the size and bit validity
-        // of `.0` is such that this transmute is sound.
-        unsafe { intrinsics::transmute_unchecked(self) }
+        // of `.0` is such that this transmute is sound, and any bits are a valid `T`.
+        unsafe { mem::transmute_unchecked_unvalidated(self) }
     }
 }
 
@@ -1,3 +1,5 @@
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 use crate::num::NonZero;
 #[cfg(debug_assertions)]
 use crate::ub_checks::assert_unsafe_precondition;
@@ -11,6 +13,7 @@ use crate::{cmp, fmt, hash, mem, num};
 #[unstable(feature = "ptr_alignment_type", issue = "102070")]
 #[derive(Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
+#[safety::invariant((self.0 as usize).is_power_of_two())]
 pub struct Alignment(AlignmentEnum);
 
 // Alignment is `repr(usize)`, but via extra steps.
@@ -21,6 +24,7 @@ fn _alignment_can_be_structurally_matched(a: Alignment) -> bool {
     matches!(a, Alignment::MIN)
 }
 
+#[safety::invariant]
 impl Alignment {
     /// The smallest possible alignment, 1.
     ///
@@ -56,6 +60,8 @@ impl Alignment {
     #[unstable(feature = "ptr_alignment_type", issue = "102070")]
     #[rustc_const_unstable(feature = "ptr_alignment_type", issue = "102070")]
     #[inline]
+    #[safety::ensures(|result| align.is_power_of_two() == result.is_some())]
+    #[safety::ensures(|result| result.is_none() || result.unwrap().as_usize() == align)]
     pub const fn new(align: usize) -> Option<Self> {
         if align.is_power_of_two() {
             // SAFETY: Just checked it only has one bit set
@@ -364,3 +370,28 @@ enum AlignmentEnum {
     _Align1Shl62 = 1 << 62,
     _Align1Shl63 = 1 << 63,
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for Alignment {
+    fn any() -> Self {
+        let align = kani::any_where(|align: &usize| align.is_power_of_two());
+        // SAFETY: just checked that `align` is a power of two.
+        unsafe { Alignment::new_unchecked(align) }
+    }
+}
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature="kani", issue="none")]
+pub(crate) mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[kani::proof_for_contract(Alignment::new)]
+    pub fn check_new() {
+        let align = kani::any::<usize>();
+        if let Some(alignment) = Alignment::new(align) {
+            assert!(alignment.is_safe());
+        }
+    }
+}
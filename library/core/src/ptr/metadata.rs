@@ -204,6 +204,10 @@ impl<Dyn: ?Sized> DynMetadata<Dyn> {
 
     /// Returns the size of the type associated with this vtable.
     #[inline]
+    #[safety::trusted(
+        reason = "vtables are created by the compiler, and their layout is not modeled"
+    )]
+    #[safety::ensures(|size| *size <= isize::MAX as usize)]
     pub fn size_of(self) -> usize {
         // Note that "size stored in vtable" is *not* the same as "result of size_of_val_raw".
         // Consider a reference like `&(i32, dyn Send)`: the vtable will only store the size of the
@@ -284,3 +288,18 @@ impl<Dyn: ?Sized> Hash for DynMetadata<Dyn> {
         crate::ptr::hash::<VTable, _>(self.vtable_ptr(), hasher)
     }
 }
+
+// Only run with random inputs, since Kani replaces the trusted `size_of` with a stand-in that
+// follows its contract, which would only verify the stand-in.
+#[cfg(safety_random)]
+#[unstable(feature = "kani", issue = "none")]
+pub(crate) mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[kani::proof_for_contract(DynMetadata::size_of)]
+    pub fn check_size_of() {
+        let value: u64 = kani::any();
+        let _ = crate::ptr::metadata(&value as &dyn fmt::Debug).size_of();
+    }
+}
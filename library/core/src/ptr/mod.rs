@@ -419,7 +419,7 @@ use crate::mem::{self, align_of, size_of, MaybeUninit};
 #[cfg(kani)]
 use crate::kani;
 
-mod alignment;
+pub(crate) mod alignment;
 #[unstable(feature = "ptr_alignment_type", issue = "102070")]
 pub use alignment::Alignment;
 
@@ -435,7 +435,7 @@ pub use crate::intrinsics::copy;
 #[doc(inline)]
 pub use crate::intrinsics::write_bytes;
 
-mod metadata;
+pub(crate) mod metadata;
 #[unstable(feature = "ptr_metadata", issue = "81513")]
 pub use metadata::{from_raw_parts, from_raw_parts_mut, metadata, DynMetadata, Pointee, Thin};
 
@@ -1023,6 +1023,11 @@ pub const unsafe fn swap<T>(x: *mut T, y: *mut T) {
 #[stable(feature = "swap_nonoverlapping", since = "1.27.0")]
 #[rustc_const_unstable(feature = "const_swap", issue = "83163")]
 #[rustc_diagnostic_item = "ptr_swap_nonoverlapping"]
+#[safety::requires(ub_checks::can_read_slice(x as *const MaybeUninit<T>, count))]
+#[safety::requires(ub_checks::can_write_slice(x, count))]
+#[safety::requires(ub_checks::can_read_slice(y as *const MaybeUninit<T>, count))]
+#[safety::requires(ub_checks::can_write_slice(y, count))]
+#[safety::requires(ub_checks::nonoverlapping(x, y, count))]
 pub const unsafe fn swap_nonoverlapping<T>(x: *mut T, y: *mut T, count: usize) {
     #[allow(unused)]
     macro_rules! attempt_swap_as_chunks {
@@ -1701,10 +1706,21 @@ pub unsafe fn read_volatile<T>(src: *const T) -> T {
                 align: usize = align_of::<T>(),
             ) => ub_checks::is_aligned_and_not_null(addr, align)
         );
-        intrinsics::volatile_load(src)
+        volatile_load(src)
     }
 }
 
+/// Performs a volatile load with the `volatile_load` intrinsic, which cannot carry a contract
+/// itself.
+#[inline]
+#[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
+#[safety::trusted(reason = "volatile accesses may have side effects outside of the program")]
+#[safety::requires(ub_checks::can_dereference(src))]
+unsafe fn volatile_load<T>(src: *const T) -> T {
+    // SAFETY: the caller must uphold the safety contract for `volatile_load`.
+    unsafe { intrinsics::volatile_load(src) }
+}
+
 /// Performs a volatile write of a memory location with the given value without
 /// reading or dropping the old value.
 ///
@@ -2295,9 +2311,9 @@ pub macro addr_of_mut($place:expr) {
     &raw mut $place
 }
 
-#[cfg(kani)]
+#[cfg(any(kani, safety_random))]
 #[unstable(feature="kani", issue="none")]
-mod verify {
+pub(crate) mod verify {
     use crate::fmt::Debug;
     use super::*;
     use crate::kani;
@@ -986,6 +986,8 @@ impl<T: ?Sized> *mut T {
     #[rustc_const_stable(feature = "const_ptr_offset_from", since = "1.65.0")]
     #[inline(always)]
     #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
+    #[rustc_allow_const_fn_unstable(const_ub_checks)]
+    #[safety::requires(ub_checks::same_allocation(self, origin))]
     pub const unsafe fn offset_from(self, origin: *const T) -> isize
     where
         T: Sized,
@@ -1078,6 +1080,8 @@ impl<T: ?Sized> *mut T {
     #[rustc_const_unstable(feature = "const_ptr_sub_ptr", issue = "95892")]
     #[inline]
     #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
+    #[safety::requires(ub_checks::same_allocation(self, origin))]
+    #[safety::ensures(|result| *result <= isize::MAX as usize)]
     pub const unsafe fn sub_ptr(self, origin: *const T) -> usize
     where
         T: Sized,
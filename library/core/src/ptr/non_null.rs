@@ -70,6 +70,7 @@ use crate::ub_checks::assert_unsafe_precondition;
 #[rustc_layout_scalar_valid_range_start(1)]
 #[rustc_nonnull_optimization_guaranteed]
 #[rustc_diagnostic_item = "NonNull"]
+#[safety::invariant(!self.pointer.is_null())]
 pub struct NonNull<T: ?Sized> {
     pointer: *const T,
 }
@@ -214,6 +215,7 @@ impl<T: ?Sized> NonNull<T> {
     #[stable(feature = "nonnull", since = "1.25.0")]
     #[rustc_const_stable(feature = "const_nonnull_new_unchecked", since = "1.25.0")]
     #[inline]
+    #[safety::invariant]
     pub const unsafe fn new_unchecked(ptr: *mut T) -> Self {
         // SAFETY: the caller must guarantee that `ptr` is non-null.
         unsafe {
@@ -41,6 +41,7 @@ impl [u8] {
     #[unstable(feature = "ascii_char", issue = "110998")]
     #[must_use]
     #[inline]
+    #[safety::requires(safety::forall!(|i in 0..self.len()| self[i].is_ascii()))]
     pub const unsafe fn as_ascii_unchecked(&self) -> &[ascii::Char] {
         let byte_ptr: *const [u8] = self;
         let ascii_ptr = byte_ptr as *const [ascii::Char];
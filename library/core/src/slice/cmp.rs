@@ -1,8 +1,9 @@
 //! Comparison traits for `[T]`.
 
 use crate::cmp::{self, BytewiseEq, Ordering};
-use crate::intrinsics::compare_bytes;
+use crate::intrinsics;
 use crate::mem;
+use crate::ub_checks;
 
 use super::from_raw_parts;
 use super::memchr;
@@ -184,6 +185,18 @@ impl<A: Ord> SliceOrd for A {
     }
 }
 
+/// Compares `[left, left + bytes)` and `[right, right + bytes)` with the `compare_bytes`
+/// intrinsic, which cannot carry a contract itself.
+#[inline]
+#[safety::trusted(reason = "lowers to `memcmp`, which is only modeled")]
+#[safety::requires(
+    ub_checks::can_read_slice(left, bytes) && ub_checks::can_read_slice(right, bytes)
+)]
+unsafe fn compare_bytes(left: *const u8, right: *const u8, bytes: usize) -> i32 {
+    // SAFETY: the caller must uphold the safety contract for `compare_bytes`.
+    unsafe { intrinsics::compare_bytes(left, right, bytes) }
+}
+
 // `compare_bytes` compares a sequence of unsigned bytes lexicographically.
 // this matches the order we want for [u8], but no others (not even [i8]).
 impl SliceOrd for u8 {
@@ -236,3 +249,27 @@ impl SliceContains for i8 {
         memchr::memchr(byte, bytes).is_some()
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+pub(crate) mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[kani::proof_for_contract(compare_bytes)]
+    pub fn check_compare_bytes() {
+        let left: [u8; 8] = kani::any();
+        let right: [u8; 8] = kani::any();
+        let bytes = kani::any_where(|bytes: &usize| *bytes <= 8);
+        let _ = unsafe { compare_bytes(left.as_ptr(), right.as_ptr(), bytes) };
+    }
+
+    #[kani::proof]
+    #[kani::stub_verified(compare_bytes)]
+    pub fn check_cmp_u8() {
+        let left: [u8; 8] = kani::any();
+        let right: [u8; 4] = kani::any();
+        let _ = left[..].cmp(&right[..]);
+        let _ = left[..4] == right[..];
+    }
+}
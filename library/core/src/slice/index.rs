@@ -149,6 +149,7 @@ mod private_slice_index {
     message = "the type `{T}` cannot be indexed by `{Self}`",
     label = "slice indices are of type `usize` or ranges of `usize`"
 )]
+#[safety::contracts]
 pub unsafe trait SliceIndex<T: ?Sized>: private_slice_index::Sealed {
     /// The output type returned by methods.
     #[stable(feature = "slice_get_slice", since = "1.28.0")]
@@ -171,6 +172,8 @@ pub unsafe trait SliceIndex<T: ?Sized>: private_slice_index::Sealed {
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
     #[unstable(feature = "slice_index_methods", issue = "none")]
+    #[safety::requires(!slice.is_null())]
+    #[safety::ensures(|result| !result.is_null())]
     unsafe fn get_unchecked(self, slice: *const T) -> *const Self::Output;
 
     /// Returns a mutable pointer to the output at this location, without
@@ -180,6 +183,8 @@ pub unsafe trait SliceIndex<T: ?Sized>: private_slice_index::Sealed {
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
     #[unstable(feature = "slice_index_methods", issue = "none")]
+    #[safety::requires(!slice.is_null())]
+    #[safety::ensures(|result| !result.is_null())]
     unsafe fn get_unchecked_mut(self, slice: *mut T) -> *mut Self::Output;
 
     /// Returns a shared reference to the output at this location, panicking
@@ -198,6 +203,7 @@ pub unsafe trait SliceIndex<T: ?Sized>: private_slice_index::Sealed {
 /// The methods `index` and `index_mut` panic if the index is out of bounds.
 #[stable(feature = "slice_get_slice_impls", since = "1.15.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for usize {
     type Output = T;
 
@@ -214,6 +220,7 @@ unsafe impl<T> SliceIndex<[T]> for usize {
     }
 
     #[inline]
+    #[safety::requires(self < slice.len())]
     unsafe fn get_unchecked(self, slice: *const [T]) -> *const T {
         assert_unsafe_precondition!(
             check_language_ub,
@@ -233,6 +240,7 @@ unsafe impl<T> SliceIndex<[T]> for usize {
     }
 
     #[inline]
+    #[safety::requires(self < slice.len())]
     unsafe fn get_unchecked_mut(self, slice: *mut [T]) -> *mut T {
         assert_unsafe_precondition!(
             check_library_ub,
@@ -259,6 +267,7 @@ unsafe impl<T> SliceIndex<[T]> for usize {
 /// Because `IndexRange` guarantees `start <= end`, fewer checks are needed here
 /// than there are for a general `Range<usize>` (which might be `100..3`).
 #[rustc_const_unstable(feature = "const_index_range_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::IndexRange {
     type Output = [T];
 
@@ -334,6 +343,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::IndexRange {
 /// - the end of the range is out of bounds.
 #[stable(feature = "slice_get_slice_impls", since = "1.15.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::Range<usize> {
     type Output = [T];
 
@@ -358,6 +368,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::Range<usize> {
     }
 
     #[inline]
+    #[safety::requires(self.start <= self.end && self.end <= slice.len())]
     unsafe fn get_unchecked(self, slice: *const [T]) -> *const [T] {
         assert_unsafe_precondition!(
             check_library_ub,
@@ -380,6 +391,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::Range<usize> {
     }
 
     #[inline]
+    #[safety::requires(self.start <= self.end && self.end <= slice.len())]
     unsafe fn get_unchecked_mut(self, slice: *mut [T]) -> *mut [T] {
         assert_unsafe_precondition!(
             check_library_ub,
@@ -423,6 +435,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::Range<usize> {
 /// The methods `index` and `index_mut` panic if the end of the range is out of bounds.
 #[stable(feature = "slice_get_slice_impls", since = "1.15.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::RangeTo<usize> {
     type Output = [T];
 
@@ -462,6 +475,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::RangeTo<usize> {
 /// The methods `index` and `index_mut` panic if the start of the range is out of bounds.
 #[stable(feature = "slice_get_slice_impls", since = "1.15.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::RangeFrom<usize> {
     type Output = [T];
 
@@ -508,6 +522,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::RangeFrom<usize> {
 
 #[stable(feature = "slice_get_slice_impls", since = "1.15.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::RangeFull {
     type Output = [T];
 
@@ -548,6 +563,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::RangeFull {
 /// - the end of the range is out of bounds.
 #[stable(feature = "inclusive_range", since = "1.26.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::RangeInclusive<usize> {
     type Output = [T];
 
@@ -593,6 +609,7 @@ unsafe impl<T> SliceIndex<[T]> for ops::RangeInclusive<usize> {
 /// The methods `index` and `index_mut` panic if the end of the range is out of bounds.
 #[stable(feature = "inclusive_range", since = "1.26.0")]
 #[rustc_const_unstable(feature = "const_slice_index", issue = "none")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for ops::RangeToInclusive<usize> {
     type Output = [T];
 
@@ -852,6 +869,7 @@ pub(crate) fn into_slice_range(
 }
 
 #[stable(feature = "slice_index_with_ops_bound_pair", since = "1.53.0")]
+#[safety::contracts]
 unsafe impl<T> SliceIndex<[T]> for (ops::Bound<usize>, ops::Bound<usize>) {
     type Output = [T];
 
@@ -887,3 +905,25 @@ unsafe impl<T> SliceIndex<[T]> for (ops::Bound<usize>, ops::Bound<usize>) {
         into_slice_range(slice.len(), self).index_mut(slice)
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+pub(crate) mod verify {
+    use super::*;
+    use crate::kani;
+
+    #[kani::proof_for_contract(<usize as SliceIndex<[u8]>>::get_unchecked)]
+    pub fn check_get_unchecked_usize() {
+        let array: [u8; 8] = kani::any();
+        let index = kani::any_where(|index: &usize| *index < 8);
+        let _ = unsafe { index.get_unchecked(&array[..] as *const [u8]) };
+    }
+
+    #[kani::proof_for_contract(<ops::Range<usize> as SliceIndex<[u8]>>::get_unchecked_mut)]
+    pub fn check_get_unchecked_mut_range() {
+        let mut array: [u8; 8] = kani::any();
+        let end = kani::any_where(|end: &usize| *end <= 8);
+        let start = kani::any_where(|start: &usize| *start <= end);
+        let _ = unsafe { (start..end).get_unchecked_mut(&mut array[..] as *mut [u8]) };
+    }
+}
@@ -34,7 +34,7 @@ pub mod memchr;
 pub mod sort;
 
 mod ascii;
-mod cmp;
+pub(crate) mod cmp;
 pub(crate) mod index;
 mod iter;
 mod raw;
@@ -4074,6 +4074,11 @@ impl<T> [T] {
     #[inline]
     #[unstable(feature = "is_sorted", reason = "new API", issue = "53485")]
     #[must_use]
+    // Only verified, since checking it at runtime would call the `PartialOrd` impl a second time,
+    // with its side effects, and a comparison that changes its answer would abort safe code.
+    #[cfg_attr(kani, safety::ensures(|result| {
+        *result == safety::forall!(|i in 1..self.len()| self[i - 1] <= self[i])
+    }))]
     pub fn is_sorted(&self) -> bool
     where
         T: PartialOrd,
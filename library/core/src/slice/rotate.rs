@@ -66,6 +66,9 @@ pub unsafe fn ptr_rotate<T>(mut left: usize, mut mid: *mut T, mut right: usize)
     if T::IS_ZST {
         return;
     }
+    // Each round rotates a part of `[mid-left, mid+right)`, which the loop invariants check against
+    // the range the caller provided. The wrapping arithmetic only computes addresses.
+    let (range_start, range_end) = (mid.wrapping_sub(left), mid.wrapping_add(right));
     loop {
         // N.B. the below algorithms can fail if these cases are not checked
         if (right == 0) || (left == 0) {
@@ -91,6 +94,8 @@ pub unsafe fn ptr_rotate<T>(mut left: usize, mut mid: *mut T, mut right: usize)
             // of reading one temporary once, copying backwards, and then writing that temporary at
             // the very end. This is possibly due to the fact that swapping or replacing temporaries
             // uses only one memory address in the loop instead of needing to manage two.
+            #[safety::loop_invariant(i < left + right && gcd <= right)]
+            #[safety::loop_invariant(range_start <= x && x.wrapping_add(i) < range_end)]
             loop {
                 // [long-safety-expl]
                 // SAFETY: callers must ensure `[left, left+mid+right)` are all valid for reading and
@@ -141,6 +146,8 @@ pub unsafe fn ptr_rotate<T>(mut left: usize, mut mid: *mut T, mut right: usize)
                 // `i < left+right` so `x+i = mid-left+i` is always valid for reading and writing
                 // according to the function's safety contract.
                 i = start + right;
+                #[safety::loop_invariant(i < left + right)]
+                #[safety::loop_invariant(range_start <= x && x.wrapping_add(i) < range_end)]
                 loop {
                     // SAFETY: see [long-safety-expl] and [safety-expl-addition]
                     tmp = unsafe { x.add(i).replace(tmp) };
@@ -199,6 +206,10 @@ pub unsafe fn ptr_rotate<T>(mut left: usize, mut mid: *mut T, mut right: usize)
             // There is an alternate way of swapping that involves finding where the last swap
             // of this algorithm would be, and swapping using that last chunk instead of swapping
             // adjacent chunks like this algorithm is doing, but this way is still faster.
+            #[safety::loop_invariant(right <= left)]
+            #[safety::loop_invariant(
+                range_start <= mid.wrapping_sub(left) && mid.wrapping_add(right) <= range_end
+            )]
             loop {
                 // SAFETY:
                 // `left >= right` so `[mid-right, mid+right)` is valid for reading and writing
@@ -215,6 +226,10 @@ pub unsafe fn ptr_rotate<T>(mut left: usize, mut mid: *mut T, mut right: usize)
             }
         } else {
             // Algorithm 3, `left < right`
+            #[safety::loop_invariant(left <= right)]
+            #[safety::loop_invariant(
+                range_start <= mid.wrapping_sub(left) && mid.wrapping_add(right) <= range_end
+            )]
             loop {
                 // SAFETY: `[mid-left, mid+left)` is valid for reading and writing because
                 // `left < right` so `mid+left < mid+right`.
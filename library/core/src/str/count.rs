@@ -70,8 +70,16 @@ fn do_count_chars(s: &str) -> usize {
 
     let mut total = char_count_general_case(head) + char_count_general_case(tail);
     // Split `body` into `CHUNK_SIZE` chunks to reduce the frequency with which
-    // we call `sum_bytes_in_usize`.
-    for chunk in body.chunks(CHUNK_SIZE) {
+    // we call `sum_bytes_in_usize`. This is a `while` loop rather than a loop
+    // over `body.chunks(CHUNK_SIZE)`, since Kani only verifies the invariants
+    // of `while` loops.
+    let mut start = 0;
+    #[safety::loop_invariant(start <= body.len() && total <= s.len())]
+    while start < body.len() {
+        let end = (start + CHUNK_SIZE).min(body.len());
+        let chunk = &body[start..end];
+        start = end;
+
         // We accumulate intermediate sums in `counts`, where each byte contains
         // a subset of the sum of this chunk, like a `[u8; size_of::<usize>()]`.
         let mut counts = 0;
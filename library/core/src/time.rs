@@ -21,6 +21,8 @@
 
 use crate::fmt;
 use crate::iter::Sum;
+#[cfg(any(kani, safety_random))]
+use crate::kani;
 use crate::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
 const NANOS_PER_SEC: u32 = 1_000_000_000;
@@ -1708,3 +1710,13 @@ impl Duration {
         )
     }
 }
+
+#[cfg(any(kani, safety_random))]
+#[unstable(feature = "kani", issue = "none")]
+impl kani::Arbitrary for Duration {
+    fn any() -> Self {
+        let nanos = kani::any_where(|nanos: &u32| *nanos < NANOS_PER_SEC);
+        // SAFETY: just checked that `nanos` is in the valid range of `Nanoseconds`.
+        Duration { secs: kani::any(), nanos: unsafe { Nanoseconds(nanos) } }
+    }
+}
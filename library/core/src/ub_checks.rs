@@ -1,6 +1,7 @@
 //! Provides the [`assert_unsafe_precondition`] macro as well as some utility functions that cover
 //! common preconditions.
 
+use crate::cmp::BytewiseEq;
 use crate::intrinsics::{self, const_eval_select};
 
 /// Check that the preconditions of an unsafe function are followed. The check is enabled at
@@ -45,11 +46,29 @@ use crate::intrinsics::{self, const_eval_select};
 /// order to call it. Since the precompiled standard library is built with full debuginfo and these
 /// variables cannot be optimized out in MIR, an innocent-looking `let` can produce enough
 /// debuginfo to have a measurable compile-time impact on debug builds.
+///
+/// When the arguments mention generic parameters of the enclosing function, those have to be
+/// redeclared on `precondition_check`, which cannot see them otherwise. This is done by listing
+/// the parameter declarations, the arguments used to instantiate them and the where clause before
+/// the argument list:
+/// `assert_unsafe_precondition!(check_library_ub, "message", [T: ?Sized, const N: usize] [T, N] where [], (ptr: *const T = ptr) => check_expr)`.
+/// Lifetime parameters may be declared but must not be listed as arguments. This form is mostly
+/// emitted by the runtime lowering of `#[safety::requires]`.
 #[allow_internal_unstable(const_ub_checks)] // permit this to be called in stably-const fn
 #[macro_export]
 #[unstable(feature = "ub_checks", issue = "none")]
 macro_rules! assert_unsafe_precondition {
     ($kind:ident, $message:expr, ($($name:ident:$ty:ty = $arg:expr),*$(,)?) => $e:expr $(,)?) => {
+        $crate::ub_checks::assert_unsafe_precondition!(
+            $kind, $message, [] [] where [], ($($name:$ty = $arg),*) => $e
+        )
+    };
+    (
+        $kind:ident,
+        $message:expr,
+        [$($generics:tt)*] [$($args:tt)*] where [$($bounds:tt)*],
+        ($($name:ident:$ty:ty = $arg:expr),*$(,)?) => $e:expr $(,)?
+    ) => {
         {
             // This check is inlineable, but not by the MIR inliner.
             // The reason for this is that the MIR inliner is in an exceptionally bad position
@@ -59,20 +78,23 @@ macro_rules! assert_unsafe_precondition {
             //
             // LLVM on the other hand sees the constant branch, so if it's `false`, it can immediately delete it without
             // inlining the check. If it's `true`, it can inline it and get significantly better performance.
+            //
+            // The check does not unwind, unless the library is built for the randomized backend
+            // of the contracts, which catches the panic, see `contract_violated`.
             #[rustc_no_mir_inline]
             #[inline]
-            #[rustc_nounwind]
+            #[cfg_attr(not(safety_random), rustc_nounwind)]
             #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
-            const fn precondition_check($($name:$ty),*) {
+            const fn precondition_check<$($generics)*>($($name:$ty),*) where $($bounds)* {
                 if !$e {
-                    ::core::panicking::panic_nounwind(
+                    ::core::ub_checks::contract_violated(
                         concat!("unsafe precondition(s) violated: ", $message)
                     );
                 }
             }
 
             if ::core::ub_checks::$kind() {
-                precondition_check($($arg,)*);
+                precondition_check::<$($args)*>($($arg,)*);
             }
         }
     };
@@ -80,6 +102,172 @@ macro_rules! assert_unsafe_precondition {
 #[unstable(feature = "ub_checks", issue = "none")]
 pub use assert_unsafe_precondition;
 
+/// Check a contract clause in place, panicking with `$message` if it does not hold. The check is
+/// enabled under the same conditions as [`assert_unsafe_precondition`], and the panic does not
+/// unwind either, unless the library is built for the randomized backend of the contracts, see
+/// [`contract_violated`].
+///
+/// Unlike [`assert_unsafe_precondition`], the condition is not moved to a separate function, so
+/// it can refer to `self`, to generic parameters of the enclosing `impl` block, or to local
+/// variables such as the value returned by the function. This macro is emitted by the runtime
+/// lowering of the `safety` contract attributes, and should not be needed otherwise.
+#[allow_internal_unstable(const_ub_checks)] // permit this to be called in stably-const fn
+#[macro_export]
+#[unstable(feature = "ub_checks", issue = "none")]
+macro_rules! check_contract {
+    ($kind:ident, $message:expr, $e:expr $(,)?) => {
+        if ::core::ub_checks::$kind() && !$e {
+            ::core::ub_checks::contract_violated($message);
+        }
+    };
+}
+#[unstable(feature = "ub_checks", issue = "none")]
+pub use check_contract;
+
+/// Report a violated contract, which is called by [`check_contract`] and
+/// [`assert_unsafe_precondition`].
+///
+/// This panics without unwinding, unless the library is built with `--cfg safety_random`: the
+/// randomized backend of the `safety` contracts runs harnesses as tests, and needs to catch the
+/// panics of the preconditions that reject an input.
+#[cfg_attr(not(safety_random), rustc_nounwind)]
+#[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+#[unstable(feature = "ub_checks", issue = "none")]
+#[inline]
+#[track_caller]
+pub const fn contract_violated(message: &'static str) -> ! {
+    #[cfg(not(safety_random))]
+    crate::panicking::panic_nounwind(message);
+    #[cfg(safety_random)]
+    crate::panicking::panic(message);
+}
+
+/// Evaluate `$e` if checks of the given kind are enabled, and return it in an `Option`.
+///
+/// This is used to record the value of `old(...)` expressions before the function body runs, so
+/// that [`check_contract`] can refer to them when checking postconditions.
+#[allow_internal_unstable(const_ub_checks)] // permit this to be called in stably-const fn
+#[macro_export]
+#[unstable(feature = "ub_checks", issue = "none")]
+macro_rules! contract_snapshot {
+    ($kind:ident, $e:expr $(,)?) => {
+        if ::core::ub_checks::$kind() {
+            ::core::option::Option::Some($e)
+        } else {
+            ::core::option::Option::None
+        }
+    };
+}
+#[unstable(feature = "ub_checks", issue = "none")]
+pub use contract_snapshot;
+
+/// Computes a hash of the bytes of `val`, which is used to check that a function does not write
+/// to memory outside of the write set declared with `#[safety::modifies]`.
+///
+/// Only the bytes of `val` itself are considered, not the ones of the memory it points to. They
+/// are only hashed if `val` has no padding and no interior mutability: integers, `bool`, `char`,
+/// and arrays, slices and strings of those, as by `BytewiseEq`. For other values, and in
+/// const-eval, this returns `0`, so that their changes go unnoticed.
+#[inline]
+pub const fn bytes_fingerprint<T: ?Sized>(val: &T) -> u64 {
+    #[inline]
+    fn runtime<T: ?Sized>(val: &T) -> u64 {
+        let ptr = val as *const T;
+        match <T as MaybeFingerprint>::hashed_len(ptr) {
+            // SAFETY: `val` is a reference, so it is valid for reads of its size, and its bytes
+            // are initialized since it has no padding.
+            Some(len) => unsafe { fingerprint(ptr as *const u8, len) },
+            None => 0,
+        }
+    }
+
+    #[inline]
+    const fn comptime<T: ?Sized>(_: &T) -> u64 {
+        0
+    }
+
+    // This is just for contract checks so we can const_eval_select.
+    const_eval_select((val,), comptime::<T>, runtime::<T>)
+}
+
+/// Computes a hash of the bytes `ptr` points to, as [`bytes_fingerprint`] does for a reference.
+///
+/// A raw pointer may not be readable, so its bytes are only hashed if they are in a live
+/// allocation of the installed [`ShadowMemory`]. Otherwise, and in Miri, whose aliasing model
+/// would see the extra reads, and in const-eval, this returns `0`.
+#[inline]
+pub const fn pointee_fingerprint<T: ?Sized>(ptr: *const T) -> u64 {
+    #[inline]
+    fn runtime<T: ?Sized>(ptr: *const T) -> u64 {
+        if cfg!(miri) {
+            return 0;
+        }
+        match <T as MaybeFingerprint>::hashed_len(ptr) {
+            Some(len) if shadow::is_live(ptr.addr(), len) => {
+                // SAFETY: the bytes are in a live allocation of the shadow memory, whose bytes
+                // are always initialized.
+                unsafe { fingerprint(ptr as *const u8, len) }
+            }
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    const fn comptime<T: ?Sized>(_: *const T) -> u64 {
+        0
+    }
+
+    // This is just for contract checks so we can const_eval_select.
+    const_eval_select((ptr,), comptime::<T>, runtime::<T>)
+}
+
+/// Computes the FNV-1a hash of `len` bytes, which is good enough to detect accidental writes.
+///
+/// # Safety
+///
+/// The bytes must be readable and initialized.
+#[inline]
+unsafe fn fingerprint(ptr: *const u8, len: usize) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for idx in 0..len {
+        // SAFETY: the caller guarantees that the bytes are readable and initialized.
+        let byte = unsafe { ptr.add(idx).read() };
+        hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Dispatches [`bytes_fingerprint`] to the types whose bytes can be hashed.
+trait MaybeFingerprint {
+    /// The number of bytes of the value `ptr` points to, if they have no padding and no interior
+    /// mutability, or `None` if they cannot be hashed.
+    fn hashed_len(ptr: *const Self) -> Option<usize>;
+}
+
+impl<T: ?Sized> MaybeFingerprint for T {
+    default fn hashed_len(_: *const T) -> Option<usize> {
+        None
+    }
+}
+
+impl<T: BytewiseEq> MaybeFingerprint for T {
+    fn hashed_len(_: *const T) -> Option<usize> {
+        Some(crate::mem::size_of::<T>())
+    }
+}
+
+impl<T: BytewiseEq> MaybeFingerprint for [T] {
+    fn hashed_len(ptr: *const [T]) -> Option<usize> {
+        crate::mem::size_of::<T>().checked_mul(ptr.len())
+    }
+}
+
+impl MaybeFingerprint for str {
+    fn hashed_len(ptr: *const str) -> Option<usize> {
+        Some(crate::ptr::metadata(ptr))
+    }
+}
+
 /// Checking library UB is always enabled when UB-checking is done
 /// (and we use a reexport so that there is no unnecessary wrapper function).
 #[unstable(feature = "ub_checks", issue = "none")]
@@ -115,16 +303,31 @@ pub(crate) const fn check_language_ub() -> bool {
 /// for `assert_unsafe_precondition!` with `check_language_ub`, in which case the
 /// check is anyway not executed in `const`.
 #[inline]
+#[safety::predicate]
 pub(crate) const fn is_aligned_and_not_null(ptr: *const (), align: usize) -> bool {
     !ptr.is_null() && ptr.is_aligned_to(align)
 }
 
 #[inline]
+#[safety::predicate]
 pub(crate) const fn is_valid_allocation_size(size: usize, len: usize) -> bool {
     let max_len = if size == 0 { usize::MAX } else { isize::MAX as usize / size };
     len <= max_len
 }
 
+/// Checks whether `data` and `len` can be the parts of a slice of `len` elements of the given
+/// `size` and `align`, as far as this can be checked without knowing the allocation of `data`.
+#[inline]
+#[safety::predicate]
+pub(crate) const fn is_valid_slice_parts(
+    data: *const (),
+    size: usize,
+    align: usize,
+    len: usize,
+) -> bool {
+    is_aligned_and_not_null(data, align) && is_valid_allocation_size(size, len)
+}
+
 /// Checks whether the regions of memory starting at `src` and `dst` of size
 /// `count * size` do *not* overlap.
 ///
@@ -161,50 +364,388 @@ pub(crate) const fn is_nonoverlapping(
     const_eval_select((src, dst, size, count), comptime, runtime)
 }
 
+/// Checks whether `ptr` can be the address of `size` bytes aligned to `align`, as far as this can
+/// be checked without knowing the allocation of `ptr`: it is not null, it is aligned, and the bytes
+/// are a valid range for [`is_valid_range`]. A zero-sized access only needs the pointer to be
+/// non-null and aligned. If a [`ShadowMemory`] is installed, the bytes must also be in one of its
+/// live allocations, unless it does not track them.
+///
+/// Note that in const-eval this function only checks that `ptr` is not null, since the address of
+/// a pointer is not known there.
+#[inline]
+#[safety::predicate]
+pub(crate) const fn is_valid_pointer(ptr: *const (), size: usize, align: usize) -> bool {
+    #[inline]
+    fn runtime(ptr: *const (), size: usize, align: usize) -> bool {
+        ptr.is_aligned_to(align) && shadow::can_access(ptr.addr(), size)
+    }
+
+    #[inline]
+    const fn comptime(_: *const (), _: usize, _: usize) -> bool {
+        true
+    }
+
+    // This is just for safety checks so we can const_eval_select.
+    !ptr.is_null()
+        && is_valid_range(ptr, size)
+        && const_eval_select((ptr, size, align), comptime, runtime)
+}
+
+/// Checks whether the `size` bytes starting at `ptr` can belong to a single allocation, which
+/// cannot be larger than `isize::MAX` bytes or wrap around the end of the address space.
+///
+/// Note that in const-eval this function just returns `true`.
+#[inline]
+#[safety::predicate]
+pub(crate) const fn is_valid_range(ptr: *const (), size: usize) -> bool {
+    #[inline]
+    fn runtime(ptr: *const (), size: usize) -> bool {
+        size <= isize::MAX as usize && ptr.addr().checked_add(size).is_some()
+    }
+
+    #[inline]
+    const fn comptime(_: *const (), _: usize) -> bool {
+        true
+    }
+
+    // This is just for safety checks so we can const_eval_select.
+    const_eval_select((ptr, size), comptime, runtime)
+}
+
 pub use predicates::*;
 
+mod shadow;
+pub use shadow::{set_shadow_memory, Shadow, ShadowMemory};
+
+mod valid_bits;
+pub use valid_bits::{is_valid_bits, ValidBits};
+
+/// A type with a safety invariant, declared with `#[safety::invariant]` on the type, which
+/// generates this impl.
+///
+/// Harnesses assume the invariant of their inputs and assert the one of their outputs with
+/// [`Invariant::is_safe`], including in other crates than the one of the type, which cannot call
+/// the `is_safe` method that the type has in its own crate.
+#[unstable(feature = "ub_checks", issue = "none")]
+pub trait Invariant {
+    /// Checks the safety invariant of `self`.
+    #[unstable(feature = "ub_checks", issue = "none")]
+    fn is_safe(&self) -> bool;
+}
+
+/// The contracts of the traits of `core` that impls in other crates inherit, which they name with
+/// `#[safety::contracts(from = ...)]`, since the modules that declare them are private.
+#[unstable(feature = "ub_checks", issue = "none")]
+pub mod contracts {
+    #[unstable(feature = "ub_checks", issue = "none")]
+    pub use crate::alloc::__safety_contracts_Allocator::Contracts as Allocator;
+    #[unstable(feature = "ub_checks", issue = "none")]
+    pub use crate::alloc::global::__safety_contracts_GlobalAlloc::Contracts as GlobalAlloc;
+}
+
 /// Provide a few predicates to be used in safety contracts.
 ///
-/// At runtime, they are no-op, and always return true.
+/// They are `const` so that they can be used in the precondition checks generated for
+/// `#[safety::requires]`. At runtime, they only check what the address of the pointer tells about
+/// its validity, with [`is_valid_pointer`], and what the [`ShadowMemory`] knows about it if one
+/// is installed. This is a sound under-approximation of the requirements: a pointer that they
+/// reject is never valid, but a pointer that they accept may still be dangling, point to memory
+/// that is not initialized, or lack the provenance to access it. Under Kani, they check the whole
+/// requirement.
 #[cfg(not(kani))]
 mod predicates {
+    use crate::mem::{align_of, size_of};
+
+    use super::{is_valid_pointer, is_valid_range, shadow};
+
     /// Checks if a pointer can be dereferenced, ensuring:
     ///   * `src` is valid for reads (see [`crate::ptr`] documentation).
     ///   * `src` is properly aligned (use `read_unaligned` if not).
     ///   * `src` points to a properly initialized value of type `T`.
     ///
+    /// At runtime, this only checks that `src` is not null, that it is aligned, and that the value
+    /// does not wrap around the address space.
+    ///
     /// [`crate::ptr`]: https://doc.rust-lang.org/std/ptr/index.html
-    pub fn can_dereference<T>(src: *const T) -> bool {
-        let _ = src;
-        true
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn can_dereference<T>(src: *const T) -> bool {
+        is_valid_pointer(src as *const (), size_of::<T>(), align_of::<T>())
     }
 
     /// Check if a pointer can be written to:
     /// * `dst` must be valid for writes.
     /// * `dst` must be properly aligned. Use `write_unaligned` if this is not the
     ///    case.
-    pub fn can_write<T>(dst: *mut T) -> bool {
-        let _ = dst;
-        true
+    ///
+    /// At runtime, this only checks that `dst` is not null, that it is aligned, and that the value
+    /// does not wrap around the address space.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn can_write<T>(dst: *mut T) -> bool {
+        is_valid_pointer(dst as *const (), size_of::<T>(), align_of::<T>())
     }
 
     /// Check if a pointer can be the target of unaligned reads.
     /// * `src` must be valid for reads.
     /// * `src` must point to a properly initialized value of type `T`.
-    pub fn can_read_unaligned<T>(src: *const T) -> bool {
-        let _ = src;
-        true
+    ///
+    /// At runtime, this only checks that `src` is not null, and that the value does not wrap
+    /// around the address space.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn can_read_unaligned<T>(src: *const T) -> bool {
+        is_valid_pointer(src as *const (), size_of::<T>(), 1)
     }
 
     /// Check if a pointer can be the target of unaligned writes.
     /// * `dst` must be valid for writes.
-    pub fn can_write_unaligned<T>(dst: *mut T) -> bool {
-        let _ = dst;
-        true
+    ///
+    /// At runtime, this only checks that `dst` is not null, and that the value does not wrap
+    /// around the address space.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn can_write_unaligned<T>(dst: *mut T) -> bool {
+        is_valid_pointer(dst as *const (), size_of::<T>(), 1)
+    }
+
+    /// Checks if `len` consecutive values of type `T` can be read from `ptr`, as by
+    /// [`can_dereference`] on each of them. This is what [`crate::slice::from_raw_parts`] requires.
+    ///
+    /// At runtime, this only checks that `ptr` is not null, that it is aligned, and that the
+    /// values fit in an allocation, which in particular means that their size does not overflow.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn can_read_slice<T>(ptr: *const T, len: usize) -> bool {
+        match size_of::<T>().checked_mul(len) {
+            Some(size) => is_valid_pointer(ptr as *const (), size, align_of::<T>()),
+            None => false,
+        }
+    }
+
+    /// Checks if `len` consecutive values of type `T` can be written to `ptr`, as by
+    /// [`can_write`] on each of them.
+    ///
+    /// At runtime, this only checks that `ptr` is not null, that it is aligned, and that the
+    /// values fit in an allocation, which in particular means that their size does not overflow.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn can_write_slice<T>(ptr: *mut T, len: usize) -> bool {
+        match size_of::<T>().checked_mul(len) {
+            Some(size) => is_valid_pointer(ptr as *const (), size, align_of::<T>()),
+            None => false,
+        }
+    }
+
+    /// Checks if a pointer to a value that may be unsized can be dereferenced, as by
+    /// [`can_dereference`] with the size and alignment given by the metadata of `src`.
+    ///
+    /// At runtime, this only checks that `src` is not null, that it is aligned, and that the
+    /// value does not wrap around the address space.
+    ///
+    /// # Safety
+    ///
+    /// The metadata of `src` must be valid, as [`crate::mem::size_of_val_raw`] requires: the size
+    /// of a slice has to fit in an `isize`, and the vtable of a trait object has to be valid. A
+    /// contract can only use this predicate if the function it belongs to requires that too.
+    #[inline]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const unsafe fn can_dereference_unsized<T: ?Sized>(src: *const T) -> bool {
+        // SAFETY: the caller guarantees that the metadata of `src` is valid.
+        let (size, align) =
+            unsafe { (crate::mem::size_of_val_raw(src), crate::mem::align_of_val_raw(src)) };
+        is_valid_pointer(src as *const (), size, align)
+    }
+
+    /// Checks if `ptr` can be offset by `count` values of type `T` while staying in bounds of its
+    /// allocation, or one byte past its end, as [`pointer::add`] requires.
+    ///
+    /// At runtime, this only checks that the offset in bytes does not overflow, and that the
+    /// values fit in an allocation. The pointer may be null or unaligned.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn is_inbounds<T>(ptr: *const T, count: usize) -> bool {
+        match size_of::<T>().checked_mul(count) {
+            Some(size) => is_valid_range(ptr as *const (), size),
+            None => false,
+        }
+    }
+
+    /// Checks if `a` and `b` can be the arguments of [`pointer::offset_from`]: they point to the
+    /// same address, or they are both derived from a pointer to the same allocated object and are
+    /// in its bounds, as by [`in_allocation_bounds`].
+    ///
+    /// At runtime, the allocated object of a pointer is usually not known, so this only checks
+    /// that two different pointers are not null and that the distance between them fits in an
+    /// `isize`, which holds for any two pointers into the same allocated object. The pointers
+    /// into the allocations of an installed [`super::ShadowMemory`] must also be in bounds of the
+    /// same live one. In const-eval, this returns `true`.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn same_allocation<T: ?Sized>(a: *const T, b: *const T) -> bool {
+        #[inline]
+        fn runtime(a: *const (), b: *const ()) -> bool {
+            a == b
+                || (!a.is_null()
+                    && !b.is_null()
+                    && a.addr().abs_diff(b.addr()) <= isize::MAX as usize
+                    && shadow::same_allocation(a.addr(), b.addr()))
+        }
+
+        #[inline]
+        const fn comptime(_: *const (), _: *const ()) -> bool {
+            true
+        }
+
+        // This is just for safety checks so we can const_eval_select.
+        crate::intrinsics::const_eval_select((a as *const (), b as *const ()), comptime, runtime)
+    }
+
+    /// Checks if `ptr` is in bounds of the allocated object it is derived from, or one byte past
+    /// its end.
+    ///
+    /// At runtime, the allocated object of a pointer is not known, so this only checks that `ptr`
+    /// is not null.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn in_allocation_bounds<T: ?Sized>(ptr: *const T) -> bool {
+        !ptr.is_null()
+    }
+
+    /// Checks if the `count` values of type `T` starting at `src` do not overlap with the ones
+    /// starting at `dst`, as [`crate::ptr::copy_nonoverlapping`] requires.
+    ///
+    /// Unlike the address arithmetic a contract would otherwise use, this does not overflow: if
+    /// the size of the values overflows a `usize`, the regions cannot both be valid, and this
+    /// returns `false`. In const-eval, this only checks the size, since the addresses of the
+    /// pointers are not known there.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn nonoverlapping<T>(src: *const T, dst: *const T, count: usize) -> bool {
+        #[inline]
+        fn runtime(src: *const (), dst: *const (), size: usize) -> bool {
+            // If the distance between the pointers is at least as big as the size of the
+            // regions, they do not overlap.
+            src.addr().abs_diff(dst.addr()) >= size
+        }
+
+        #[inline]
+        const fn comptime(_: *const (), _: *const (), _: usize) -> bool {
+            true
+        }
+
+        let Some(size) = size_of::<T>().checked_mul(count) else { return false };
+        // This is just for safety checks so we can const_eval_select.
+        crate::intrinsics::const_eval_select(
+            (src as *const (), dst as *const (), size),
+            comptime,
+            runtime,
+        )
+    }
+
+    /// Checks if the `len` values of type `T` starting at `ptr` are initialized, except for their
+    /// padding, as [`crate::mem::MaybeUninit::assume_init`] requires.
+    ///
+    /// The initialization of memory is not tracked at runtime, so this returns `true`, unless an
+    /// installed [`super::ShadowMemory`] knows that one of the values has not been written at
+    /// all. Under Kani, it is checked by the instrumentation of uninitialized memory, which is
+    /// enabled with `-Z uninit-checks`. In const-eval, this returns `true`.
+    #[inline]
+    #[safety::predicate]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    pub const fn is_initialized<T>(ptr: *const T, len: usize) -> bool {
+        #[inline]
+        fn runtime(ptr: *const (), size: usize, len: usize) -> bool {
+            shadow::is_initialized(ptr.addr(), size, len)
+        }
+
+        #[inline]
+        const fn comptime(_: *const (), _: usize, _: usize) -> bool {
+            true
+        }
+
+        // Values whose size overflows a `usize` cannot be initialized.
+        if size_of::<T>().checked_mul(len).is_none() {
+            return false;
+        }
+        // This is just for safety checks so we can const_eval_select.
+        crate::intrinsics::const_eval_select(
+            (ptr as *const (), size_of::<T>(), len),
+            comptime,
+            runtime,
+        )
     }
 }
 
 #[cfg(kani)]
 mod predicates {
     pub use crate::kani::mem::{can_dereference, can_write, can_read_unaligned, can_write_unaligned};
+    use crate::ptr::{slice_from_raw_parts, slice_from_raw_parts_mut};
+
+    /// Checks if `len` consecutive values of type `T` can be read from `ptr`.
+    pub fn can_read_slice<T>(ptr: *const T, len: usize) -> bool {
+        can_dereference(slice_from_raw_parts(ptr, len))
+    }
+
+    /// Checks if `len` consecutive values of type `T` can be written to `ptr`.
+    pub fn can_write_slice<T>(ptr: *mut T, len: usize) -> bool {
+        can_write(slice_from_raw_parts_mut(ptr, len))
+    }
+
+    /// Checks if a pointer to a value that may be unsized can be dereferenced.
+    ///
+    /// # Safety
+    ///
+    /// The metadata of `src` must be valid, as for the runtime version.
+    pub unsafe fn can_dereference_unsized<T: ?Sized>(src: *const T) -> bool {
+        can_dereference(src)
+    }
+
+    /// Checks if `ptr` can be offset by `count` values of type `T` while staying in bounds of its
+    /// allocation.
+    pub fn is_inbounds<T>(ptr: *const T, count: usize) -> bool {
+        crate::kani::mem::is_inbounds(slice_from_raw_parts(ptr, count))
+    }
+
+    /// Checks if `a` and `b` point to the same address, or are both in bounds of the same allocated
+    /// object.
+    pub fn same_allocation<T: ?Sized>(a: *const T, b: *const T) -> bool {
+        let (a, b) = (a as *const u8, b as *const u8);
+        a == b
+            || (crate::kani::mem::same_allocation(a, b)
+                && in_allocation_bounds(a)
+                && in_allocation_bounds(b))
+    }
+
+    /// Checks if `ptr` is in bounds of its allocated object, or one byte past its end, which is
+    /// the case if it or the byte before it can be read.
+    pub fn in_allocation_bounds<T: ?Sized>(ptr: *const T) -> bool {
+        let ptr = ptr as *const u8;
+        crate::kani::mem::is_inbounds(ptr) || crate::kani::mem::is_inbounds(ptr.wrapping_sub(1))
+    }
+
+    /// Checks if the `count` values of type `T` starting at `src` do not overlap with the ones
+    /// starting at `dst`. Pointers to different allocated objects never overlap, and the distance
+    /// between pointers to the same one is compared to the size of the values, which must not
+    /// overflow.
+    pub fn nonoverlapping<T>(src: *const T, dst: *const T, count: usize) -> bool {
+        let Some(size) = crate::mem::size_of::<T>().checked_mul(count) else { return false };
+        !crate::kani::mem::same_allocation(src, dst) || src.addr().abs_diff(dst.addr()) >= size
+    }
+
+    /// Checks if the `len` values of type `T` starting at `ptr` are initialized, except for their
+    /// padding, which requires the instrumentation of uninitialized memory.
+    pub fn is_initialized<T>(ptr: *const T, len: usize) -> bool {
+        crate::kani::mem::is_initialized(slice_from_raw_parts(ptr, len))
+    }
 }
@@ -0,0 +1,124 @@
+//! The shadow memory that the runtime predicates of [`super`] query, when a test installs one with
+//! [`set_shadow_memory`], such as the shadow allocator of the tests of `alloc`.
+
+#[cfg(target_has_atomic = "ptr")]
+use crate::ptr;
+#[cfg(target_has_atomic = "ptr")]
+use crate::sync::atomic::{AtomicPtr, Ordering};
+
+/// The state of an address in a [`ShadowMemory`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shadow {
+    /// The address is not in an allocation that the shadow memory tracks.
+    Untracked,
+    /// The address is in a live allocation of `size` bytes starting at `start`, or close enough to
+    /// its bounds that it cannot be in another allocation.
+    Live { start: usize, size: usize },
+    /// The address is in an allocation that has been freed, and is not reused yet.
+    Freed,
+}
+
+/// Memory whose allocations are tracked, which the runtime predicates can ask about the
+/// pointers they are given.
+///
+/// The allocations must be surrounded by bytes that are neither in another allocation nor
+/// untracked, so that the address one byte past the end of an allocation is known to belong to
+/// it. The methods are called from the precondition checks of the library, so they must not
+/// panic, and must not call functions whose contracts query the shadow memory while it is locked.
+pub trait ShadowMemory: Sync {
+    /// The state of the byte at `addr`.
+    fn lookup(&self, addr: usize) -> Shadow;
+
+    /// Whether each of the `count` values of `size` bytes starting at `addr` may be initialized.
+    /// This returns `false` only if one of them is known not to be, because none of its bytes
+    /// has been written. The padding of a value cannot be told apart from its other bytes here.
+    fn is_initialized(&self, addr: usize, size: usize, count: usize) -> bool;
+}
+
+#[cfg(target_has_atomic = "ptr")]
+static SHADOW: AtomicPtr<&'static dyn ShadowMemory> = AtomicPtr::new(ptr::null_mut());
+
+/// Makes the runtime predicates query `memory` about the pointers into its allocations, or stop
+/// querying a shadow memory if it is `None`.
+///
+/// This is meant for tests: the predicates then reject pointers that are out of bounds of the
+/// live allocations of `memory` or into freed ones, and values that are not initialized. They are
+/// unchanged for the pointers that `memory` does not track. This does nothing on targets without
+/// atomic pointers.
+pub fn set_shadow_memory(memory: Option<&'static &'static dyn ShadowMemory>) {
+    #[cfg(target_has_atomic = "ptr")]
+    {
+        let memory = memory.map_or(ptr::null_mut(), |memory| ptr::from_ref(memory).cast_mut());
+        SHADOW.store(memory, Ordering::Release);
+    }
+    #[cfg(not(target_has_atomic = "ptr"))]
+    let _ = memory;
+}
+
+/// The installed shadow memory, if any.
+fn shadow_memory() -> Option<&'static dyn ShadowMemory> {
+    #[cfg(target_has_atomic = "ptr")]
+    {
+        // SAFETY: `SHADOW` is either null, or was stored from a `'static` reference.
+        unsafe { SHADOW.load(Ordering::Acquire).as_ref() }.copied()
+    }
+    #[cfg(not(target_has_atomic = "ptr"))]
+    {
+        None
+    }
+}
+
+/// Checks whether the `size` bytes at `addr`, which do not wrap around the address space, can be
+/// accessed as far as the shadow memory knows: they are in a single live allocation, or are not
+/// tracked.
+#[safety::predicate(reason = "the shadow memory is only queried, and its queries must not panic")]
+pub(crate) fn can_access(addr: usize, size: usize) -> bool {
+    let Some(memory) = shadow_memory() else { return true };
+    if size == 0 {
+        return true;
+    }
+    match memory.lookup(addr) {
+        Shadow::Untracked => true,
+        Shadow::Live { start, size: len } => start <= addr && addr + size <= start + len,
+        Shadow::Freed => false,
+    }
+}
+
+/// Checks whether the `size` bytes at `addr` are known to be in a single live allocation of the
+/// shadow memory, and can therefore be read.
+pub(crate) fn is_live(addr: usize, size: usize) -> bool {
+    let Some(memory) = shadow_memory() else { return false };
+    match memory.lookup(addr) {
+        Shadow::Live { start, size: len } => {
+            start <= addr && addr.checked_add(size).is_some_and(|end| end <= start + len)
+        }
+        Shadow::Untracked | Shadow::Freed => false,
+    }
+}
+
+/// Checks whether two different addresses can be in bounds of the same allocation, or one byte
+/// past its end, as far as the shadow memory knows.
+#[safety::predicate(reason = "the shadow memory is only queried, and its queries must not panic")]
+pub(crate) fn same_allocation(a: usize, b: usize) -> bool {
+    let Some(memory) = shadow_memory() else { return true };
+    // Two untracked addresses may be in bounds of an allocation that is not tracked.
+    match (memory.lookup(a), memory.lookup(b)) {
+        (Shadow::Untracked, Shadow::Untracked) => true,
+        (Shadow::Live { start, size }, Shadow::Live { start: other, .. }) => {
+            start == other
+                && (start..=start + size).contains(&a)
+                && (start..=start + size).contains(&b)
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the `count` values of `size` bytes at `addr` may be initialized, as far as the
+/// shadow memory knows.
+#[safety::predicate(reason = "the shadow memory is only queried, and its queries must not panic")]
+pub(crate) fn is_initialized(addr: usize, size: usize, count: usize) -> bool {
+    match shadow_memory() {
+        Some(memory) => memory.is_initialized(addr, size, count),
+        None => true,
+    }
+}
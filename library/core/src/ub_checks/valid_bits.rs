@@ -0,0 +1,208 @@
+//! The bit validity of types, which the contracts of the functions that create a value out of the
+//! bytes of another one, such as [`crate::mem::transmute_unchecked`], check with
+//! [`is_valid_bits`].
+
+use crate::ascii::Char as AsciiChar;
+use crate::intrinsics::const_eval_select;
+use crate::mem::{offset_of, size_of, MaybeUninit};
+use crate::num::NonZero;
+use crate::ptr::NonNull;
+
+/// Types whose valid values can be told apart from other bytes.
+///
+/// This is implemented for the primitive types, for `NonZero` integers, for references,
+/// `Option<NonNull<T>>` and `MaybeUninit<T>`, and for arrays and tuples of such types. It can be
+/// derived for `repr(C)` and `repr(transparent)` structs whose fields implement it, by checking
+/// each field, and for fieldless enums with an integer `repr`, by comparing the discriminant with
+/// the one of each variant.
+///
+/// # Safety
+///
+/// [`ValidBits::is_valid_bits`] must return `true` if and only if the bytes are a valid value of
+/// `Self`, since the contracts that use it are verified under this assumption.
+pub unsafe trait ValidBits: Sized {
+    /// Checks whether the `size_of::<Self>()` bytes at `ptr` are a valid value of `Self`.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must be readable, and the ones that are not padding of `Self` must be
+    /// initialized. `ptr` does not need to be aligned.
+    unsafe fn is_valid_bits(ptr: *const u8) -> bool;
+}
+
+/// The derive macro of [`ValidBits`].
+pub use safety::ValidBits;
+
+/// Checks whether the `size_of::<T>()` bytes at `ptr` are a valid value of type `T`, as by
+/// [`ValidBits`].
+///
+/// Any bytes are accepted in const-eval. Under Kani, the same checks are verified.
+///
+/// # Safety
+///
+/// The bytes must be readable, and the ones that are not padding of `T` must be initialized. A
+/// contract can establish this first, for instance with [`super::can_read_slice`].
+#[inline]
+#[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+pub const unsafe fn is_valid_bits<T: ValidBits>(ptr: *const u8) -> bool {
+    #[inline]
+    fn runtime<T: ValidBits>(ptr: *const u8) -> bool {
+        // SAFETY: the caller guarantees that the bytes are readable and initialized.
+        unsafe { T::is_valid_bits(ptr) }
+    }
+
+    #[inline]
+    const fn comptime<T: ValidBits>(_: *const u8) -> bool {
+        true
+    }
+
+    // This is just for safety checks so we can const_eval_select.
+    const_eval_select((ptr,), comptime::<T>, runtime::<T>)
+}
+
+/// Reads a value of type `T` from the possibly unaligned `ptr`.
+///
+/// # Safety
+///
+/// The `size_of::<T>()` bytes at `ptr` must be readable and initialized, and be a valid `T`.
+#[inline]
+unsafe fn read<T>(ptr: *const u8) -> T {
+    // SAFETY: the caller guarantees that the bytes are a readable and valid `T`.
+    unsafe { ptr.cast::<T>().read_unaligned() }
+}
+
+/// The types for which any initialized bytes are a valid value.
+macro_rules! any_bits {
+    ($($ty:ty),*) => {$(
+        // SAFETY: any initialized bytes are a valid value of this type.
+        unsafe impl ValidBits for $ty {
+            #[inline]
+            unsafe fn is_valid_bits(_: *const u8) -> bool {
+                true
+            }
+        }
+    )*};
+}
+
+any_bits!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f16, f32, f64, f128);
+
+macro_rules! nonzero_bits {
+    ($($ty:ty),*) => {$(
+        // SAFETY: a `NonZero` integer is valid if it is not zero.
+        unsafe impl ValidBits for NonZero<$ty> {
+            #[inline]
+            unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+                // SAFETY: the caller guarantees that the bytes are readable and initialized, and
+                // the integer has the same size.
+                unsafe { read::<$ty>(ptr) != 0 }
+            }
+        }
+    )*};
+}
+
+nonzero_bits!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// SAFETY: a `bool` is valid if its byte is 0 or 1.
+unsafe impl ValidBits for bool {
+    #[inline]
+    unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+        // SAFETY: the caller guarantees that the byte is readable and initialized.
+        unsafe { *ptr <= 1 }
+    }
+}
+
+// SAFETY: a `char` is valid if it is a Unicode scalar value, as checked by `char::from_u32`.
+unsafe impl ValidBits for char {
+    #[inline]
+    unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+        // SAFETY: the caller guarantees that the bytes are readable and initialized, and a `char`
+        // has the size of an `u32`.
+        char::from_u32(unsafe { read::<u32>(ptr) }).is_some()
+    }
+}
+
+// SAFETY: an `AsciiChar` is valid if its byte is below 128.
+unsafe impl ValidBits for AsciiChar {
+    #[inline]
+    unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+        // SAFETY: the caller guarantees that the byte is readable and initialized.
+        unsafe { *ptr }.is_ascii()
+    }
+}
+
+// SAFETY: a `NonNull` is valid if its address is not zero.
+unsafe impl<T> ValidBits for NonNull<T> {
+    #[inline]
+    unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+        // SAFETY: the caller guarantees that the bytes are readable and initialized, and any
+        // initialized bytes are a valid raw pointer.
+        !unsafe { read::<*const T>(ptr) }.is_null()
+    }
+}
+
+// SAFETY: any initialized bytes are a valid `Option<NonNull<T>>`, since `None` is represented
+// by the null pointer.
+unsafe impl<T> ValidBits for Option<NonNull<T>> {
+    #[inline]
+    unsafe fn is_valid_bits(_: *const u8) -> bool {
+        true
+    }
+}
+
+// SAFETY: any bytes are a valid `MaybeUninit<T>`, initialized or not.
+unsafe impl<T> ValidBits for MaybeUninit<T> {
+    #[inline]
+    unsafe fn is_valid_bits(_: *const u8) -> bool {
+        true
+    }
+}
+
+macro_rules! reference_bits {
+    ($($ty:ty),*) => {$(
+        // SAFETY: a reference is valid if it is not null and aligned. Whether it points to a valid
+        // value is part of the safety invariant of the reference, which cannot be checked here.
+        unsafe impl<T> ValidBits for $ty {
+            #[inline]
+            unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+                // SAFETY: the caller guarantees that the bytes are readable and initialized, and
+                // any initialized bytes are a valid raw pointer.
+                let ptr = unsafe { read::<*const T>(ptr) };
+                !ptr.is_null() && ptr.is_aligned()
+            }
+        }
+    )*};
+}
+
+reference_bits!(&T, &mut T);
+
+// SAFETY: an array is valid if each of its elements is.
+unsafe impl<T: ValidBits, const N: usize> ValidBits for [T; N] {
+    #[inline]
+    unsafe fn is_valid_bits(ptr: *const u8) -> bool {
+        // SAFETY: the elements are in the bytes of the array, which the caller guarantees to be
+        // readable and initialized.
+        (0..N).all(|i| unsafe { T::is_valid_bits(ptr.add(i * size_of::<T>())) })
+    }
+}
+
+macro_rules! tuple_bits {
+    ($($name:ident $index:tt)*) => {
+        // SAFETY: a tuple is valid if each of its fields is, and its padding can be anything.
+        unsafe impl<$($name: ValidBits),*> ValidBits for ($($name,)*) {
+            #[inline]
+            unsafe fn is_valid_bits(_ptr: *const u8) -> bool {
+                // SAFETY: the fields are in the bytes of the tuple, which the caller guarantees to
+                // be readable and initialized.
+                true $(&& unsafe { $name::is_valid_bits(_ptr.add(offset_of!(Self, $index))) })*
+            }
+        }
+    };
+}
+
+tuple_bits!();
+tuple_bits!(A 0);
+tuple_bits!(A 0 B 1);
+tuple_bits!(A 0 B 1 C 2);
+tuple_bits!(A 0 B 1 C 2 D 3);
+tuple_bits!(A 0 B 1 C 2 D 3 E 4);
+tuple_bits!(A 0 B 1 C 2 D 3 E 4 F 5);
@@ -0,0 +1,51 @@
+//! The harnesses of `core`, which run with pseudo-random inputs under the randomized backend of the
+//! `safety` contracts, since `core` has no unit tests to turn them into.
+
+#[test]
+fn harnesses() {
+    for &(name, harness) in core::kani::HARNESSES.iter().copied().flatten() {
+        safety::run_harness!(name, harness);
+    }
+}
+
+/// Every harness in a `verify` module of `core` is listed in `core::kani::HARNESSES`, which is
+/// maintained by hand. A harness is recognized as the first function after a `kani::proof`
+/// attribute, and its path is the one of the file that declares the module.
+#[test]
+fn harnesses_are_listed() {
+    use std::fs;
+    use std::path::Path;
+
+    fn visit(dir: &Path, module: &str, missing: &mut Vec<String>, listed: &[&str]) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            if path.is_dir() {
+                visit(&path, &format!("{module}::{name}"), missing, listed);
+                continue;
+            }
+            let module = match name {
+                "lib" | "mod" => module.to_string(),
+                _ => format!("{module}::{name}"),
+            };
+            let source = fs::read_to_string(&path).unwrap();
+            let Some((_, verify)) = source.split_once("mod verify {") else { continue };
+            for (_, harness) in
+                verify.split("#[kani::proof").skip(1).filter_map(|s| s.split_once("fn "))
+            {
+                let harness =
+                    harness.split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap();
+                let harness = format!("{module}::verify::{harness}");
+                if !listed.contains(&harness.as_str()) {
+                    missing.push(harness);
+                }
+            }
+        }
+    }
+
+    let listed: Vec<_> =
+        core::kani::HARNESSES.iter().copied().flatten().map(|&(name, _)| name).collect();
+    let mut missing = Vec::new();
+    visit(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), "core", &mut missing, &listed);
+    assert!(missing.is_empty(), "harnesses missing from `core::kani::HARNESSES`: {missing:?}");
+}
@@ -119,6 +119,7 @@
 #![feature(is_ascii_octdigit)]
 #![feature(get_many_mut)]
 #![feature(iter_map_windows)]
+#![cfg_attr(safety_random, feature(kani))]
 #![allow(internal_features)]
 #![deny(unsafe_op_in_unsafe_fn)]
 #![deny(fuzzy_provenance_casts)]
@@ -136,6 +137,8 @@ mod char;
 mod clone;
 mod cmp;
 mod const_ptr;
+#[cfg(safety_random)]
+mod contracts;
 mod convert;
 mod fmt;
 mod future;
@@ -4,6 +4,7 @@ use crate::{
 };
 
 #[stable(feature = "alloc_system_type", since = "1.28.0")]
+#[safety::contracts(from = core::ub_checks::contracts::GlobalAlloc)]
 unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
@@ -8,6 +8,7 @@ use crate::alloc::{GlobalAlloc, Layout, System};
 use crate::sync::OnceLock;
 
 #[stable(feature = "alloc_system_type", since = "1.28.0")]
+#[safety::contracts(from = core::ub_checks::contracts::GlobalAlloc)]
 unsafe impl GlobalAlloc for System {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         static EFI_MEMORY_TYPE: OnceLock<u32> = OnceLock::new();
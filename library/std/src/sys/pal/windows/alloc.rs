@@ -208,6 +208,7 @@ unsafe fn allocate(layout: Layout, zeroed: bool) -> *mut u8 {
 // Instead there will be a header readable directly before the returned pointer, containing the actual
 // location of the start of the block.
 #[stable(feature = "alloc_system_type", since = "1.28.0")]
+#[safety::contracts(from = core::ub_checks::contracts::GlobalAlloc)]
 unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {